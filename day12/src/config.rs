@@ -0,0 +1,58 @@
+use std::{
+    fmt::{Display, Formatter},
+    path::PathBuf,
+};
+
+#[derive(Default)]
+pub struct Config {
+    // where to write the regions as an svg, if anywhere
+    pub svg_path: Option<PathBuf>,
+    pub print_largest_region: bool,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    MissingValue(String),
+    UnknownArgument(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Error::*;
+        match self {
+            MissingValue(flag) => write!(f, "No value given for '{flag}'"),
+            UnknownArgument(arg) => {
+                write!(
+                    f,
+                    "Unknown argument '{arg}', expected --svg FILE or --largest-region"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Config {
+    // --svg FILE writes the regions to FILE, --largest-region describes the
+    // region with the most plots
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Config, Error> {
+        let mut config = Config::default();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--svg" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| Error::MissingValue(arg.clone()))?;
+                    config.svg_path = Some(PathBuf::from(path));
+                }
+                "--largest-region" => config.print_largest_region = true,
+                _ => return Err(Error::UnknownArgument(arg)),
+            }
+        }
+
+        Ok(config)
+    }
+}
@@ -4,7 +4,7 @@ use enum_iterator::Sequence;
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 
-#[derive(Debug, Clone, Copy, FromPrimitive, Sequence, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, FromPrimitive, Sequence, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum Direction {
     North,
@@ -23,6 +23,33 @@ impl Direction {
     }
 }
 
+pub fn get_neighbor_in_direction(
+    position: (usize, usize),
+    direction: Direction,
+    num_rows: usize,
+    num_columns: usize,
+) -> Option<(usize, usize)> {
+    use Direction::*;
+    match direction {
+        North => position.0.checked_sub(1).map(|row| (row, position.1)),
+        East => {
+            if position.1 + 1 < num_columns {
+                Some((position.0, position.1 + 1))
+            } else {
+                None
+            }
+        }
+        South => {
+            if position.0 + 1 < num_rows {
+                Some((position.0 + 1, position.1))
+            } else {
+                None
+            }
+        }
+        West => position.1.checked_sub(1).map(|column| (position.0, column)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Direction;
@@ -33,15 +60,12 @@ mod tests {
     fn test_sequence_value_order_as_expected() {
         assert_eq!(
             vec![0, 1, 2, 3],
-            all::<Direction>()
-                .map(|dir| (dir as u8))
-                .collect::<Vec<_>>()
+            all::<Direction>().map(|dir| dir as u8).collect::<Vec<_>>()
         );
 
         assert_eq!(
             all::<Direction>().collect::<Vec<_>>(),
             (0..4)
-                .into_iter()
                 .map(|i| Direction::from_usize(i).unwrap())
                 .collect::<Vec<_>>()
         );
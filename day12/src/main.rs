@@ -1,11 +1,16 @@
+mod config;
 mod direction;
-
-use std::array;
+mod outline;
+mod regions;
+mod svg;
 
 use array2d::Array2D;
 
-use direction::Direction;
-use enum_iterator::{all, cardinality};
+use config::Config;
+use regions::{regions, Connectivity};
+use svg::regions_to_svg;
+
+const SVG_CELL_SIZE: usize = 20;
 
 fn parse_input(input: &str) -> Array2D<u8> {
     let num_rows = input.lines().count();
@@ -21,164 +26,62 @@ fn parse_input(input: &str) -> Array2D<u8> {
     .unwrap()
 }
 
-pub fn get_neighbor_in_direction(
-    position: (usize, usize),
-    direction: Direction,
-    num_rows: usize,
-    num_columns: usize,
-) -> Option<(usize, usize)> {
-    use Direction::*;
-    match direction {
-        North => position.0.checked_sub(1).map(|row| (row, position.1)),
-        East => {
-            if position.1 + 1 < num_columns {
-                Some((position.0, position.1 + 1))
-            } else {
-                None
-            }
-        }
-        South => {
-            if position.0 + 1 < num_rows {
-                Some((position.0 + 1, position.1))
-            } else {
-                None
-            }
-        }
-        West => position.1.checked_sub(1).map(|column| (position.0, column)),
-    }
-}
-
-fn get_neighbors(
-    position: (usize, usize),
-    num_rows: usize,
-    num_columns: usize,
-) -> impl Iterator<Item = (usize, usize)> {
-    all::<Direction>().flat_map(move |direction| {
-        get_neighbor_in_direction(position, direction, num_rows, num_columns).into_iter()
-    })
-}
-
 fn part1(grid: &Array2D<u8>) -> usize {
-    let mut seen = Array2D::filled_with(false, grid.num_rows(), grid.num_columns());
-    let mut result = 0;
-
-    for start in grid.indices_row_major() {
-        if seen[start] {
-            continue;
-        }
-
-        seen[start] = true;
-
-        let region_plant = grid[start];
-        let mut stack = vec![start];
-        let mut area = 0;
-        let mut perimeter = 0;
-
-        while let Some(position) = stack.pop() {
-            area += 1;
-            perimeter += 4;
-
-            for neighbor in get_neighbors(position, grid.num_rows(), grid.num_columns()) {
-                if grid[neighbor] == region_plant {
-                    perimeter -= 1;
-
-                    if !seen[neighbor] {
-                        seen[neighbor] = true;
-                        stack.push(neighbor);
-                    }
-                }
-            }
-        }
-
-        result += area * perimeter;
-    }
-
-    result
+    regions(grid, Connectivity::Four)
+        .iter()
+        .map(|region| region.area() * region.perimeter)
+        .sum()
 }
 
 fn part2(grid: &Array2D<u8>) -> usize {
-    let mut seen = Array2D::filled_with(false, grid.num_rows(), grid.num_columns());
-    let mut seen_fence_in_direction: [Array2D<bool>; cardinality::<Direction>()] =
-        array::from_fn(|_| Array2D::filled_with(false, grid.num_rows(), grid.num_columns()));
-    let mut result = 0;
-
-    let is_fence_in_direction = |position, direction| match get_neighbor_in_direction(
-        position,
-        direction,
-        grid.num_rows(),
-        grid.num_columns(),
-    ) {
-        Some(neighbor) => grid[neighbor] != grid[position],
-        None => true,
-    };
-
-    for start in grid.indices_row_major() {
-        if seen[start] {
-            continue;
-        }
-
-        seen[start] = true;
-
-        let region_plant = grid[start];
-        let mut stack = vec![start];
-        let mut area = 0;
-        let mut num_sides = 0;
-
-        while let Some(position) = stack.pop() {
-            area += 1;
-
-            for direction in all::<Direction>() {
-                let seen_fence = &mut seen_fence_in_direction[direction as usize];
-                if !seen_fence[position] && is_fence_in_direction(position, direction) {
-                    num_sides += 1;
-                    seen_fence[position] = true;
-                    for perp_direction in [direction.turn_ccw(), direction.turn_cw()] {
-                        let mut cur_position = position;
-
-                        loop {
-                            cur_position = match get_neighbor_in_direction(
-                                cur_position,
-                                perp_direction,
-                                grid.num_rows(),
-                                grid.num_columns(),
-                            ) {
-                                Some(pos) => pos,
-                                None => break,
-                            };
-
-                            if grid[cur_position] == region_plant
-                                && is_fence_in_direction(cur_position, direction)
-                            {
-                                seen_fence[cur_position] = true;
-                            } else {
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-
-            for neighbor in get_neighbors(position, grid.num_rows(), grid.num_columns()) {
-                if grid[neighbor] == region_plant && !seen[neighbor] {
-                    seen[neighbor] = true;
-                    stack.push(neighbor);
-                }
-            }
-        }
+    regions(grid, Connectivity::Four)
+        .iter()
+        .map(|region| region.area() * region.num_sides())
+        .sum()
+}
 
-        result += area * num_sides;
-    }
+fn print_largest_region(grid: &Array2D<u8>) {
+    let all_regions = regions(grid, Connectivity::Four);
+    let Some(region) = all_regions.iter().max_by_key(|region| region.area()) else {
+        return;
+    };
 
-    result
+    println!(
+        "Largest region: plant {}, area {}, bounding box {:?}, {} holes",
+        region.label as char,
+        region.area(),
+        region.bounding_box,
+        region.num_holes
+    );
 }
 
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::from_args(std::env::args().skip(1))?;
+
     let file_contents_as_str = include_str!("../input.txt");
 
     let input = parse_input(file_contents_as_str);
 
     println!("{}", part1(&input));
     println!("{}", part2(&input));
+
+    if config.print_largest_region {
+        print_largest_region(&input);
+    }
+
+    if let Some(svg_path) = config.svg_path {
+        let svg = regions_to_svg(
+            &regions(&input, Connectivity::Four),
+            Connectivity::Four,
+            input.num_rows(),
+            input.num_columns(),
+            SVG_CELL_SIZE,
+        );
+        std::fs::write(&svg_path, svg)?;
+        println!("Wrote regions to {}", svg_path.display());
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -237,4 +140,115 @@ mod tests {
     fn test_part2(input_str: &str) -> usize {
         part2(&parse_input(input_str))
     }
+
+    #[test]
+    fn test_regions() {
+        let grid = parse_input(TEST_INPUT_MEDIUM);
+        let four_regions = regions(&grid, Connectivity::Four);
+        assert_eq!(5, four_regions.len());
+
+        let o_region = four_regions.iter().find(|r| r.label == b'O').unwrap();
+        assert_eq!(21, o_region.area());
+        assert_eq!(36, o_region.perimeter);
+        assert_eq!(20, o_region.num_sides());
+        assert_eq!(((0, 0), (4, 4)), o_region.bounding_box);
+        assert_eq!(4, o_region.num_holes);
+
+        for x_region in four_regions.iter().filter(|r| r.label == b'X') {
+            assert_eq!(
+                (1, 4, 4, 0),
+                (
+                    x_region.area(),
+                    x_region.perimeter,
+                    x_region.num_sides(),
+                    x_region.num_holes
+                )
+            );
+        }
+
+        // the two B blocks touch at a corner, so they join up with 8-connectivity,
+        // but holes in the A region use 4-connectivity, so each block is its own hole
+        let grid = parse_input(TEST_INPUT_AB);
+        let eight_regions = regions(&grid, Connectivity::Eight);
+        assert_eq!(2, eight_regions.len());
+        let b_region = eight_regions.iter().find(|r| r.label == b'B').unwrap();
+        assert_eq!(8, b_region.area());
+        assert_eq!(((1, 1), (4, 4)), b_region.bounding_box);
+        assert_eq!(0, b_region.num_holes);
+        let a_region = eight_regions.iter().find(|r| r.label == b'A').unwrap();
+        assert_eq!(2, a_region.num_holes);
+    }
+
+    #[test_case(TEST_INPUT_MEDIUM, Connectivity::Four ; "medium")]
+    #[test_case(TEST_INPUT_E, Connectivity::Four ; "big e")]
+    #[test_case(TEST_INPUT_AB, Connectivity::Four ; "ab")]
+    #[test_case(TEST_INPUT_AB, Connectivity::Eight ; "ab eight")]
+    #[test_case(TEST_INPUT_BIG, Connectivity::Four ; "big")]
+    fn test_outlines(input_str: &str, connectivity: Connectivity) {
+        let grid = parse_input(input_str);
+        for region in regions(&grid, connectivity) {
+            let outline = outline::trace_outline(&region, connectivity);
+            let num_corners = outline
+                .outers
+                .iter()
+                .chain(outline.holes.iter())
+                .map(|corners| corners.len())
+                .sum::<usize>();
+            assert_eq!(region.num_sides(), num_corners);
+            assert_eq!(region.num_holes, outline.holes.len());
+        }
+    }
+
+    #[test]
+    fn test_regions_to_svg() {
+        let grid = parse_input(TEST_INPUT_SMALL);
+        let svg = regions_to_svg(
+            &regions(&grid, Connectivity::Four),
+            Connectivity::Four,
+            grid.num_rows(),
+            grid.num_columns(),
+            10,
+        );
+
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="40">"#)
+        );
+        assert_eq!(5, svg.matches("<path ").count());
+
+        // each plant's fill, and its discounted price written on its first cell
+        for (label, hue, price, text_position) in [
+            ('A', 175, 16, (5, 5)),
+            ('B', 222, 16, (5, 15)),
+            ('C', 269, 32, (25, 15)),
+            ('D', 316, 4, (35, 15)),
+            ('E', 3, 12, (5, 35)),
+        ] {
+            let fill_and_title = format!(
+                "fill=\"hsl({hue}, 70%, 60%)\" fill-rule=\"evenodd\" stroke=\"black\">\n    <title>plant {label}: "
+            );
+            assert_eq!(1, svg.matches(&fill_and_title).count(), "{label}");
+            assert!(
+                svg.contains(&format!(
+                    r#"<text x="{}" y="{}" font-size="5" text-anchor="middle" dominant-baseline="central">{price}</text>"#,
+                    text_position.0, text_position.1
+                )),
+                "{label}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_config_from_args() {
+        let config = Config::from_args(["--svg", "regions.svg"].map(String::from)).unwrap();
+        assert_eq!(Some("regions.svg".into()), config.svg_path);
+
+        assert!(!config.print_largest_region);
+
+        let config = Config::from_args(["--largest-region".to_string()]).unwrap();
+        assert!(config.print_largest_region);
+        assert!(config.svg_path.is_none());
+
+        assert!(Config::from_args(["--svg".to_string()]).is_err());
+        assert!(Config::from_args(["regions.svg".to_string()]).is_err());
+    }
 }
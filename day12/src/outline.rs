@@ -0,0 +1,109 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    direction::Direction,
+    regions::{Connectivity, Region, Side},
+};
+
+// corners are (row, column) on the lines between cells, so a grid with
+// n rows has corners in rows 0..=n
+pub type Corner = (usize, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outline {
+    // clockwise on screen, one per piece of an 8-connected region
+    pub outers: Vec<Vec<Corner>>,
+    // counter clockwise on screen, one per hole
+    pub holes: Vec<Vec<Corner>>,
+}
+
+fn step_corner((row, column): Corner, direction: Direction) -> Corner {
+    use Direction::*;
+    match direction {
+        North => (row - 1, column),
+        East => (row, column + 1),
+        South => (row + 1, column),
+        West => (row, column - 1),
+    }
+}
+
+// the corner the fence on the given side of a cell starts from, walked so the
+// region is on its right
+fn fence_start((row, column): (usize, usize), direction: Direction) -> Corner {
+    use Direction::*;
+    match direction {
+        North => (row, column),
+        East => (row, column + 1),
+        South => (row + 1, column + 1),
+        West => (row + 1, column),
+    }
+}
+
+// where the side starts and ends, and which way it heads in between
+fn side_corners(side: &Side) -> (Corner, Corner, Direction) {
+    let heading = side.direction.turn_cw();
+    let start = fence_start(side.first, side.direction);
+    let end = step_corner(fence_start(side.last, side.direction), heading);
+    (start, end, heading)
+}
+
+// twice the signed area, positive when clockwise on screen
+fn signed_area(corners: &[Corner]) -> isize {
+    corners
+        .iter()
+        .zip(corners.iter().cycle().skip(1))
+        .map(|(&(y1, x1), &(y2, x2))| (x1 * y2) as isize - (x2 * y1) as isize)
+        .sum()
+}
+
+// joins the region's sides end to end into closed loops, so each loop has one
+// corner per side. Where the region touches itself at a corner, two sides
+// start there, and the loop keeps the two cells apart with 4-connectivity and
+// walks round them together with 8-connectivity.
+pub fn trace_outline<T>(region: &Region<T>, connectivity: Connectivity) -> Outline {
+    let sides = region.sides.iter().map(side_corners).collect::<Vec<_>>();
+
+    let mut sides_from_corner: HashMap<Corner, Vec<usize>> = HashMap::new();
+    for (i, &(start, _, _)) in sides.iter().enumerate() {
+        sides_from_corner.entry(start).or_default().push(i);
+    }
+
+    // a side always turns into the next one, never carries straight on
+    let next_side = |i: usize| {
+        let (_, end, heading) = sides[i];
+        let turn = match connectivity {
+            Connectivity::Four => heading.turn_cw(),
+            Connectivity::Eight => heading.turn_ccw(),
+        };
+        let candidates = &sides_from_corner[&end];
+        candidates
+            .iter()
+            .copied()
+            .find(|&j| sides[j].2 == turn)
+            .unwrap_or(candidates[0])
+    };
+
+    let mut unwalked = (0..sides.len()).collect::<HashSet<_>>();
+    let mut loops = Vec::new();
+
+    while let Some(&start) = unwalked.iter().next() {
+        let mut corners = Vec::new();
+        let mut side = start;
+        loop {
+            unwalked.remove(&side);
+            corners.push(sides[side].0);
+            side = next_side(side);
+            if side == start {
+                break;
+            }
+        }
+
+        loops.push(corners);
+    }
+
+    let (outers, holes) = loops
+        .into_iter()
+        .partition(|corners| signed_area(corners) > 0);
+
+    Outline { outers, holes }
+}
@@ -0,0 +1,266 @@
+use std::array;
+
+use array2d::Array2D;
+use enum_iterator::{all, cardinality};
+
+use crate::direction::{get_neighbor_in_direction, Direction};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    // cells only join up through their edges
+    Four,
+    // cells also join up through their corners
+    Eight,
+}
+
+impl Connectivity {
+    fn complement(&self) -> Connectivity {
+        match self {
+            Connectivity::Four => Connectivity::Eight,
+            Connectivity::Eight => Connectivity::Four,
+        }
+    }
+}
+
+// a straight run of fence along the given side of a row or column of cells.
+// first and last are the cells at each end, in the order the fence is walked
+// with the region on its right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Side {
+    pub direction: Direction,
+    pub first: (usize, usize),
+    pub last: (usize, usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Region<T> {
+    pub label: T,
+    pub cells: Vec<(usize, usize)>,
+    pub perimeter: usize,
+    pub sides: Vec<Side>,
+    // top left and bottom right corners, inclusive
+    pub bounding_box: ((usize, usize), (usize, usize)),
+    pub num_holes: usize,
+}
+
+impl<T> Region<T> {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn num_sides(&self) -> usize {
+        self.sides.len()
+    }
+}
+
+fn get_neighbors(
+    position: (usize, usize),
+    connectivity: Connectivity,
+    num_rows: usize,
+    num_columns: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    let diagonals = (connectivity == Connectivity::Eight)
+        .then(|| {
+            all::<Direction>().filter_map(move |direction| {
+                let neighbor =
+                    get_neighbor_in_direction(position, direction, num_rows, num_columns)?;
+                get_neighbor_in_direction(neighbor, direction.turn_cw(), num_rows, num_columns)
+            })
+        })
+        .into_iter()
+        .flatten();
+
+    all::<Direction>()
+        .filter_map(move |direction| {
+            get_neighbor_in_direction(position, direction, num_rows, num_columns)
+        })
+        .chain(diagonals)
+}
+
+// flood fills every region of equal labels, returning the region index of every cell
+fn label_regions<T: Eq>(
+    grid: &Array2D<T>,
+    connectivity: Connectivity,
+) -> (Array2D<usize>, Vec<Vec<(usize, usize)>>) {
+    let mut region_ids = Array2D::filled_with(usize::MAX, grid.num_rows(), grid.num_columns());
+    let mut region_cells = Vec::new();
+
+    for start in grid.indices_row_major() {
+        if region_ids[start] != usize::MAX {
+            continue;
+        }
+
+        let region_id = region_cells.len();
+        region_ids[start] = region_id;
+
+        let mut cells = vec![];
+        let mut stack = vec![start];
+
+        while let Some(position) = stack.pop() {
+            cells.push(position);
+
+            for neighbor in
+                get_neighbors(position, connectivity, grid.num_rows(), grid.num_columns())
+            {
+                if grid[neighbor] == grid[start] && region_ids[neighbor] == usize::MAX {
+                    region_ids[neighbor] = region_id;
+                    stack.push(neighbor);
+                }
+            }
+        }
+
+        region_cells.push(cells);
+    }
+
+    (region_ids, region_cells)
+}
+
+// counts the components of cells outside the region that can't reach
+// the edge of the grid, using the opposite connectivity to the region's
+fn count_holes(
+    region_ids: &Array2D<usize>,
+    region_id: usize,
+    connectivity: Connectivity,
+    ((min_row, min_column), (max_row, max_column)): ((usize, usize), (usize, usize)),
+) -> usize {
+    // the bounding box plus a border of one cell all the way around,
+    // shifted so the border starts at (0, 0).
+    // the border is outside the region, and so is anything off the grid
+    let num_rows = max_row - min_row + 3;
+    let num_columns = max_column - min_column + 3;
+    let is_outside = Array2D::from_iter_row_major(
+        (0..num_rows)
+            .flat_map(|i| (0..num_columns).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let position = (
+                    (i + min_row).checked_sub(1),
+                    (j + min_column).checked_sub(1),
+                );
+                match position {
+                    (Some(row), Some(column)) => region_ids
+                        .get(row, column)
+                        .is_none_or(|&id| id != region_id),
+                    _ => true,
+                }
+            }),
+        num_rows,
+        num_columns,
+    )
+    .unwrap();
+
+    let mut seen = Array2D::filled_with(false, num_rows, num_columns);
+    let mut num_components = 0;
+
+    for start in is_outside.indices_row_major() {
+        if seen[start] || !is_outside[start] {
+            continue;
+        }
+
+        num_components += 1;
+        seen[start] = true;
+        let mut stack = vec![start];
+
+        while let Some(position) = stack.pop() {
+            for neighbor in
+                get_neighbors(position, connectivity.complement(), num_rows, num_columns)
+            {
+                if is_outside[neighbor] && !seen[neighbor] {
+                    seen[neighbor] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+    }
+
+    // the first component found is the one wrapping around the border
+    num_components - 1
+}
+
+pub fn regions<T: Eq + Clone>(grid: &Array2D<T>, connectivity: Connectivity) -> Vec<Region<T>> {
+    let (num_rows, num_columns) = (grid.num_rows(), grid.num_columns());
+    let (region_ids, region_cells) = label_regions(grid, connectivity);
+
+    let is_fence_in_direction = |position, direction| match get_neighbor_in_direction(
+        position,
+        direction,
+        num_rows,
+        num_columns,
+    ) {
+        Some(neighbor) => region_ids[neighbor] != region_ids[position],
+        None => true,
+    };
+
+    let mut seen_fence_in_direction: [Array2D<bool>; cardinality::<Direction>()] =
+        array::from_fn(|_| Array2D::filled_with(false, num_rows, num_columns));
+
+    region_cells
+        .into_iter()
+        .enumerate()
+        .map(|(region_id, cells)| {
+            let mut perimeter = 0;
+            let mut sides = Vec::new();
+
+            for &position in cells.iter() {
+                for direction in all::<Direction>() {
+                    if !is_fence_in_direction(position, direction) {
+                        continue;
+                    }
+
+                    perimeter += 1;
+
+                    // walk along the fence both ways so the rest of the side
+                    // isn't counted again, ending up at the cells on each end
+                    let seen_fence = &mut seen_fence_in_direction[direction as usize];
+                    if seen_fence[position] {
+                        continue;
+                    }
+
+                    seen_fence[position] = true;
+
+                    let [first, last] =
+                        [direction.turn_ccw(), direction.turn_cw()].map(|perp_direction| {
+                            let mut cur_position = position;
+
+                            while let Some(next_position) = get_neighbor_in_direction(
+                                cur_position,
+                                perp_direction,
+                                num_rows,
+                                num_columns,
+                            ) {
+                                if region_ids[next_position] != region_id
+                                    || !is_fence_in_direction(next_position, direction)
+                                {
+                                    break;
+                                }
+                                seen_fence[next_position] = true;
+                                cur_position = next_position;
+                            }
+
+                            cur_position
+                        });
+
+                    sides.push(Side {
+                        direction,
+                        first,
+                        last,
+                    });
+                }
+            }
+
+            let min_row = cells.iter().map(|p| p.0).min().unwrap();
+            let max_row = cells.iter().map(|p| p.0).max().unwrap();
+            let min_column = cells.iter().map(|p| p.1).min().unwrap();
+            let max_column = cells.iter().map(|p| p.1).max().unwrap();
+            let bounding_box = ((min_row, min_column), (max_row, max_column));
+
+            Region {
+                label: grid[cells[0]].clone(),
+                perimeter,
+                sides,
+                bounding_box,
+                num_holes: count_holes(&region_ids, region_id, connectivity, bounding_box),
+                cells,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,89 @@
+use std::fmt::Write;
+
+use crate::{
+    outline::{trace_outline, Corner},
+    regions::{Connectivity, Region},
+};
+
+// spreads neighbouring letters around the colour wheel
+fn plant_colour(label: u8) -> String {
+    format!("hsl({}, 70%, 60%)", (label as usize * 47) % 360)
+}
+
+fn path_data(loops: &[Vec<Corner>], cell_size: usize) -> String {
+    loops
+        .iter()
+        .map(|corners| {
+            let points = corners
+                .iter()
+                .map(|&(row, column)| format!("{},{}", column * cell_size, row * cell_size))
+                .collect::<Vec<_>>();
+            format!("M{}Z", points.join(" L"))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// draws every region as its outline with the holes cut out, labelled with its
+// bulk discount price (area times sides) at the cell the flood fill started from
+pub fn regions_to_svg(
+    regions: &[Region<u8>],
+    connectivity: Connectivity,
+    num_rows: usize,
+    num_columns: usize,
+    cell_size: usize,
+) -> String {
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}">"#,
+        num_columns * cell_size,
+        num_rows * cell_size
+    )
+    .unwrap();
+
+    for region in regions {
+        let outline = trace_outline(region, connectivity);
+        let loops = outline
+            .outers
+            .into_iter()
+            .chain(outline.holes)
+            .collect::<Vec<_>>();
+
+        writeln!(
+            svg,
+            r#"  <path d="{}" fill="{}" fill-rule="evenodd" stroke="black">"#,
+            path_data(&loops, cell_size),
+            plant_colour(region.label)
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "    <title>plant {}: area {}, perimeter {}, sides {}, price {}, discounted price {}</title>",
+            region.label as char,
+            region.area(),
+            region.perimeter,
+            region.num_sides(),
+            region.area() * region.perimeter,
+            region.area() * region.num_sides()
+        )
+        .unwrap();
+        writeln!(svg, "  </path>").unwrap();
+
+        let (row, column) = region.cells[0];
+        writeln!(
+            svg,
+            r#"  <text x="{}" y="{}" font-size="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+            column * cell_size + cell_size / 2,
+            row * cell_size + cell_size / 2,
+            cell_size / 2,
+            region.area() * region.num_sides()
+        )
+        .unwrap();
+    }
+
+    writeln!(svg, "</svg>").unwrap();
+
+    svg
+}
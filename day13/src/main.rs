@@ -1,24 +1,43 @@
 mod util;
 
-use util::{parse_machines, Machine};
+use util::{parse_machines, Error, Machine};
 
-fn run(input: &[Machine]) -> u64 {
-    input.iter().filter_map(Machine::fewest_tokens_to_win).sum()
+const PART_1_PRESS_LIMIT: i128 = 100;
+
+// machines whose prize can't be reached are skipped
+fn run(input: &[Machine]) -> Result<u128, Error> {
+    input
+        .iter()
+        .map(Machine::fewest_tokens_to_win)
+        .filter(|tokens| *tokens != Err(Error::Unreachable))
+        .sum()
 }
 
-fn main() {
-    let file_contents_as_str = include_str!("../input.txt");
+// reads the machines from the file given on the command line, if there is one
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let file_contents = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../input.txt").to_string(),
+    };
+
+    let input = parse_machines(&file_contents)?;
 
-    let input = parse_machines(file_contents_as_str);
+    let part1_input = input
+        .iter()
+        .cloned()
+        .map(|machine| machine.with_press_limit(PART_1_PRESS_LIMIT))
+        .collect::<Vec<_>>();
 
-    println!("{}", run(&input));
+    println!("{}", run(&part1_input)?);
 
     let part2_input = input
         .into_iter()
         .map(Machine::into_part_2)
         .collect::<Vec<_>>();
 
-    println!("{}", run(&part2_input));
+    println!("{}", run(&part2_input)?);
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -29,7 +48,45 @@ mod tests {
 
     #[test]
     fn test_run() {
-        let input = parse_machines(TEST_INPUT);
-        assert_eq!(480, run(&input))
+        let input = parse_machines(TEST_INPUT).unwrap();
+        assert_eq!(Ok(480), run(&input));
+
+        let part2_input = input
+            .into_iter()
+            .map(Machine::into_part_2)
+            .collect::<Vec<_>>();
+        assert_eq!(Ok(875318608908), run(&part2_input));
+    }
+
+    #[test]
+    fn test_collinear_buttons() {
+        let cases = [
+            // five b presses beat ten a presses
+            ("X+10, Y+10", "X+20, Y+20", "X=100, Y=100", Ok(5)),
+            // the only answers are one a press and two b presses
+            ("X+15, Y+15", "X+20, Y+20", "X=55, Y=55", Ok(5)),
+            // two a presses beat eight b presses
+            ("X+40, Y+40", "X+10, Y+10", "X=80, Y=80", Ok(6)),
+            (
+                "X+10, Y+10",
+                "X+20, Y+20",
+                "X=100, Y=90",
+                Err(Error::Unreachable),
+            ),
+            (
+                "X+20, Y+20",
+                "X+40, Y+40",
+                "X=50, Y=50",
+                Err(Error::Unreachable),
+            ),
+        ];
+
+        for (a_button, b_button, prize, expected) in cases {
+            let machines = parse_machines(&format!(
+                "Button A: {a_button}\nButton B: {b_button}\nPrize: {prize}"
+            ))
+            .unwrap();
+            assert_eq!(expected, machines[0].fewest_tokens_to_win(), "{machines:?}");
+        }
     }
 }
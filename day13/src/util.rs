@@ -1,5 +1,8 @@
 mod button;
 mod machine;
+mod parse_error;
 mod prize;
+mod solver;
 
 pub use machine::{parse_machines, Machine};
+pub use solver::Error;
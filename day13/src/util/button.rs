@@ -1,38 +1,68 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use super::parse_error::{parse_number, Reason};
+
 static BUTTON_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^Button (A|B): X\+([1-9][0-9]), Y\+([1-9][0-9])$").unwrap());
+    Lazy::new(|| Regex::new(r"^Button (\w+): X([+-]\d+), Y([+-]\d+)(?:, Cost (\d+))?$").unwrap());
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Button {
-    pub x: i64,
-    pub y: i64,
+    pub x: i128,
+    pub y: i128,
+    // tokens per press
+    pub cost: i128,
+    pub max_presses: Option<i128>,
 }
 
 impl Button {
-    fn from_button_str(button_str: &str, expected_label: char) -> Self {
-        let (_, [label, x_str, y_str]) = BUTTON_REGEX
-            .captures(button_str)
-            .expect(button_str)
-            .extract();
-
-        let mut chrs = label.chars();
-        if !(chrs.next() == Some(expected_label) && chrs.next() == None) {
-            panic!("Wrong button label '{label}' expected to be '{expected_label}' in line: '{button_str}'");
-        }
-
+    pub fn new(x: i128, y: i128, cost: i128) -> Self {
         Button {
-            x: x_str.parse().unwrap(),
-            y: y_str.parse().unwrap(),
+            x,
+            y,
+            cost,
+            max_presses: None,
         }
     }
 
-    pub fn from_a_button_str(button_str: &str) -> Self {
-        Button::from_button_str(button_str, 'A')
+    pub fn allows_presses(&self, presses: i128) -> bool {
+        presses >= 0
+            && self
+                .max_presses
+                .is_none_or(|max_presses| presses <= max_presses)
     }
 
-    pub fn from_b_button_str(button_str: &str) -> Self {
-        Button::from_button_str(button_str, 'B')
+    // the puzzle's A button costs 3 tokens a press and its B button costs 1.
+    // Buttons with any other label need their cost given explicitly.
+    fn default_cost(label: &str) -> Option<i128> {
+        match label {
+            "A" => Some(3),
+            "B" => Some(1),
+            _ => None,
+        }
+    }
+
+    // a line like 'Button A: X+94, Y+34' or 'Button C: X+1, Y+1, Cost 2',
+    // along with the button's label
+    pub fn from_button_str(button_str: &str) -> Result<(String, Self), Reason> {
+        let captures = BUTTON_REGEX
+            .captures(button_str)
+            .ok_or_else(|| Reason::MalformedButton(button_str.to_string()))?;
+        let label = &captures[1];
+        let cost = match captures.get(4) {
+            Some(cost_str) => parse_number(cost_str.as_str())?,
+            None => {
+                Button::default_cost(label).ok_or_else(|| Reason::UnknownCost(label.to_string()))?
+            }
+        };
+
+        Ok((
+            label.to_string(),
+            Button::new(
+                parse_number(&captures[2])?,
+                parse_number(&captures[3])?,
+                cost,
+            ),
+        ))
     }
 }
@@ -1,130 +1,125 @@
-use itertools::Itertools;
+use std::collections::HashSet;
 
-use num_integer::{ExtendedGcd, Integer};
+use itertools::Itertools;
 
 use super::button::Button;
+use super::parse_error::{ParseError, Reason};
 use super::prize::Prize;
+use super::solver::{cheapest_presses, Error};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Machine {
-    a_button: Button,
-    b_button: Button,
+    buttons: Vec<Button>,
     prize: Prize,
 }
 
 impl Machine {
+    pub fn new(buttons: Vec<Button>, prize: Prize) -> Self {
+        Machine { buttons, prize }
+    }
+
     pub fn into_part_2(mut self) -> Machine {
         self.prize.x += 10000000000000;
         self.prize.y += 10000000000000;
         self
     }
 
-    fn check(&self, a_presses: i64, b_presses: i64) {
-        assert!(
-            a_presses >= 0
-                && b_presses >= 0
-                && (self.a_button.x * a_presses + self.b_button.x * b_presses == self.prize.x)
-                && (self.a_button.y * a_presses + self.b_button.y * b_presses == self.prize.y),
-            "{:?}, a_presses = {}, b_presses = {}",
-            self,
-            a_presses,
-            b_presses
-        );
-    }
-
-    pub fn fewest_tokens_to_win(&self) -> Option<u64> {
-        if let Some((a_presses, b_presses)) = self.fewest_tokens_to_win_helper() {
-            self.check(a_presses, b_presses);
-            Some((3 * a_presses + b_presses) as u64)
-        } else {
-            None
+    pub fn with_press_limit(mut self, max_presses: i128) -> Machine {
+        for button in self.buttons.iter_mut() {
+            button.max_presses = Some(max_presses);
         }
+        self
     }
 
-    fn fewest_tokens_to_win_helper(&self) -> Option<(i64, i64)> {
-        // solve where:
-        // a.x * a_presses + b.x * b_presses = prize.x
-        // a.y * a_presses + b.y * b_presses = prize.y
-        let ExtendedGcd {
-            gcd: gcd_x,
-            x: s_x,
-            y: t_x,
-            ..
-        } = self.a_button.x.extended_gcd(&self.b_button.x);
-
-        if self.prize.x % gcd_x != 0 {
-            return None;
-        }
+    fn is_solution(&self, presses: &[i128]) -> bool {
+        let lands_on = |axis: fn(&Button) -> i128, prize: i128| {
+            self.buttons
+                .iter()
+                .zip(presses)
+                .try_fold(0i128, |moved, (button, &count)| {
+                    moved.checked_add(axis(button).checked_mul(count)?)
+                })
+                == Some(prize)
+        };
 
-        let ExtendedGcd {
-            gcd: gcd_y,
-            x: s_y,
-            y: t_y,
-            ..
-        } = self.a_button.y.extended_gcd(&self.b_button.y);
-
-        if self.prize.y % gcd_y != 0 {
-            return None;
-        }
-
-        let p_prime_x = self.prize.x / gcd_x;
-
-        if (self.a_button.x * self.b_button.y) == (self.a_button.y * self.b_button.x) {
-            if self.a_button.y * (s_y * (self.prize.y / gcd_y) - s_x * (self.prize.x / gcd_x))
-                == self.b_button.y * (t_x * (self.prize.x / gcd_x) - t_y * (self.prize.y / gcd_y))
-            {
-                assert!(s_x > 0);
-                // v_0 = t_x * prize_x / gcd_x + a_x / b_x * (s_x * prize_x / gcd_x - u_0)
-                let a_presses = (s_x * p_prime_x).rem_euclid(self.b_button.x / gcd_x);
-                let b_presses = t_x * p_prime_x
-                    + self.a_button.x * ((s_x * p_prime_x - a_presses) / self.b_button.x);
-
-                if b_presses < 0 {
-                    return None;
-                } else {
-                    return Some((a_presses, b_presses));
-                }
-            } else {
-                return None;
-            }
-        }
-
-        let denominator =
-            (self.a_button.x * self.b_button.y - self.a_button.y * self.b_button.x) / gcd_x;
-
-        let p_prime_y = self.prize.y / gcd_y;
-
-        let numerator = self.a_button.y * (s_y * p_prime_y - s_x * p_prime_x)
-            + self.b_button.y * (t_y * p_prime_y - t_x * p_prime_x);
-
-        if numerator % denominator != 0 {
-            return None;
-        }
+        self.buttons
+            .iter()
+            .zip(presses)
+            .all(|(button, &count)| button.allows_presses(count))
+            && lands_on(|button| button.x, self.prize.x)
+            && lands_on(|button| button.y, self.prize.y)
+    }
 
-        let k = numerator / denominator;
+    pub fn fewest_tokens_to_win(&self) -> Result<u128, Error> {
+        let presses = cheapest_presses(&self.buttons, (self.prize.x, self.prize.y))?;
+        debug_assert!(
+            self.is_solution(&presses),
+            "{self:?}, presses = {presses:?}"
+        );
 
-        let a_presses = s_x * p_prime_x - (self.b_button.x / gcd_x) * k;
-        let b_presses = t_x * p_prime_x + (self.a_button.x / gcd_x) * k;
-        return Some((a_presses, b_presses));
+        let tokens = self
+            .buttons
+            .iter()
+            .zip(presses)
+            .try_fold(0i128, |tokens, (button, count)| {
+                tokens.checked_add(button.cost.checked_mul(count)?)
+            })
+            .ok_or(Error::Overflow)?;
+        Ok(tokens as u128)
     }
 }
 
-pub fn parse_machines(input: &str) -> Vec<Machine> {
+// each machine is one or more buttons with different labels followed by
+// its prize, and machines are separated by blank lines. Extra whitespace
+// around lines and Windows line endings are fine.
+pub fn parse_machines(input: &str) -> Result<Vec<Machine>, ParseError> {
     input
         .lines()
-        .chunk_by(|&line| !line.is_empty())
+        .map(str::trim)
+        .enumerate()
+        .chunk_by(|(_, line)| !line.is_empty())
         .into_iter()
         .filter_map(|(key, chunk)| if key { Some(chunk) } else { None })
-        .map(|mut chunk| {
-            let (a_str, b_str, prize_str) = chunk
-                .next_tuple()
-                .expect("Chunk in input does not contain 3 lines");
-
-            Machine {
-                a_button: Button::from_a_button_str(a_str),
-                b_button: Button::from_b_button_str(b_str),
-                prize: prize_str.into(),
+        .enumerate()
+        .map(|(machine_index, chunk)| {
+            let lines = chunk.collect::<Vec<_>>();
+            let error_at = |line_index: usize| {
+                move |reason| ParseError {
+                    machine: machine_index + 1,
+                    line: line_index + 1,
+                    reason,
+                }
+            };
+            let after_last_line = lines.last().unwrap().0 + 1;
+            let mut lines = lines.into_iter().peekable();
+
+            let mut buttons = Vec::new();
+            let mut labels = HashSet::new();
+            while let Some((button_index, button_str)) =
+                lines.next_if(|(_, line)| line.starts_with("Button"))
+            {
+                let (label, button) =
+                    Button::from_button_str(button_str).map_err(error_at(button_index))?;
+                if !labels.insert(label.clone()) {
+                    return Err(error_at(button_index)(Reason::DuplicateButton(label)));
+                }
+                buttons.push(button);
+            }
+
+            let (prize_index, prize_str) = lines
+                .next()
+                .ok_or_else(|| error_at(after_last_line)(Reason::MissingPrize))?;
+            if buttons.is_empty() {
+                return Err(error_at(prize_index)(Reason::NoButtons));
+            }
+            let prize = prize_str.parse().map_err(error_at(prize_index))?;
+            if let Some((extra_index, extra_str)) = lines.next() {
+                return Err(error_at(extra_index)(Reason::ExtraLine(
+                    extra_str.to_string(),
+                )));
             }
+
+            Ok(Machine::new(buttons, prize))
         })
         .collect()
 }
@@ -137,31 +132,221 @@ mod tests {
 
     #[test]
     fn test_parse_machines() {
-        let machines = parse_machines(TEST_INPUT);
+        let machines = parse_machines(TEST_INPUT).unwrap();
+
+        let expected_machines = vec![
+            Machine::new(
+                vec![Button::new(94, 34, 3), Button::new(22, 67, 1)],
+                Prize { x: 8400, y: 5400 },
+            ),
+            Machine::new(
+                vec![Button::new(26, 66, 3), Button::new(67, 21, 1)],
+                Prize { x: 12748, y: 12176 },
+            ),
+            Machine::new(
+                vec![Button::new(17, 86, 3), Button::new(84, 37, 1)],
+                Prize { x: 7870, y: 6450 },
+            ),
+            Machine::new(
+                vec![Button::new(69, 23, 3), Button::new(27, 71, 1)],
+                Prize { x: 18641, y: 10279 },
+            ),
+        ];
+
+        assert_eq!(expected_machines, machines);
+    }
+
+    #[test]
+    fn test_fewest_tokens_to_win_with_any_buttons() {
+        let diagonal_limited = Button {
+            max_presses: Some(1),
+            ..Button::new(1, 1, 1)
+        };
+        let cases = [
+            (vec![Button::new(2, 3, 4)], Prize { x: 4, y: 6 }, Ok(8)),
+            // three diagonal presses and two across
+            (
+                vec![
+                    Button::new(1, 0, 1),
+                    Button::new(0, 1, 1),
+                    Button::new(1, 1, 1),
+                ],
+                Prize { x: 5, y: 3 },
+                Ok(5),
+            ),
+            (
+                vec![Button::new(1, 0, 1), Button::new(0, 1, 1), diagonal_limited],
+                Prize { x: 5, y: 3 },
+                Ok(7),
+            ),
+            (
+                vec![
+                    Button::new(2, 0, 1),
+                    Button::new(0, 2, 1),
+                    Button::new(2, 2, 1),
+                ],
+                Prize { x: 1, y: 1 },
+                Err(Error::Unreachable),
+            ),
+            (
+                vec![
+                    Button::new(1, 0, 1),
+                    Button::new(-1, 0, 1),
+                    Button::new(2, 0, 1),
+                ],
+                Prize { x: 3, y: 0 },
+                Err(Error::Unbounded),
+            ),
+        ];
+
+        for (buttons, prize, expected) in cases {
+            let machine = Machine::new(buttons, prize);
+            assert_eq!(expected, machine.fewest_tokens_to_win(), "{machine:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_lenient_machines() {
+        let input = "\r\n\
+                     Button A: X+123456789012, Y-5\r\n\
+                     Button B: X-7, Y+0  \r\n\
+                     Prize: X=0, Y=-100000000000000000000\r\n\
+                     \r\n\
+                     \r\n\
+                     \x20 Button A: X+1, Y+2\n\
+                     Button B: X+3, Y+4\n\
+                     Prize: X=5, Y=6\n";
 
         let expected_machines = vec![
-            Machine {
-                a_button: Button { x: 94, y: 34 },
-                b_button: Button { x: 22, y: 67 },
-                prize: Prize { x: 8400, y: 5400 },
-            },
-            Machine {
-                a_button: Button { x: 26, y: 66 },
-                b_button: Button { x: 67, y: 21 },
-                prize: Prize { x: 12748, y: 12176 },
-            },
-            Machine {
-                a_button: Button { x: 17, y: 86 },
-                b_button: Button { x: 84, y: 37 },
-                prize: Prize { x: 7870, y: 6450 },
-            },
-            Machine {
-                a_button: Button { x: 69, y: 23 },
-                b_button: Button { x: 27, y: 71 },
-                prize: Prize { x: 18641, y: 10279 },
-            },
+            Machine::new(
+                vec![Button::new(123456789012, -5, 3), Button::new(-7, 0, 1)],
+                Prize {
+                    x: 0,
+                    y: -100000000000000000000,
+                },
+            ),
+            Machine::new(
+                vec![Button::new(1, 2, 3), Button::new(3, 4, 1)],
+                Prize { x: 5, y: 6 },
+            ),
         ];
 
+        assert_eq!(Ok(expected_machines), parse_machines(input));
+
+        // button C is given a cost of 1 token a press, so the first prize takes
+        // three presses of C and two of A
+        let input = "Button A: X+1, Y+0\n\
+                     Button B: X+0, Y+1\n\
+                     Button C: X+1, Y+1, Cost 1\n\
+                     Prize: X=5, Y=3\n\
+                     \n\
+                     Button B: X+2, Y+2\n\
+                     Prize: X=4, Y=4";
+
+        let expected_machines = vec![
+            Machine::new(
+                vec![
+                    Button::new(1, 0, 3),
+                    Button::new(0, 1, 1),
+                    Button::new(1, 1, 1),
+                ],
+                Prize { x: 5, y: 3 },
+            ),
+            Machine::new(vec![Button::new(2, 2, 1)], Prize { x: 4, y: 4 }),
+        ];
+
+        let machines = parse_machines(input).unwrap();
         assert_eq!(expected_machines, machines);
+        assert_eq!(
+            vec![Ok(9), Ok(2)],
+            machines
+                .iter()
+                .map(Machine::fewest_tokens_to_win)
+                .collect::<Vec<_>>()
+        );
+
+        // lots of machines with big numbers, like a generated stress test
+        let input = (1..=1000)
+            .map(|i| {
+                format!(
+                    "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+                    i * 1000,
+                    i * 3,
+                    i,
+                    i * 2000,
+                    i * 1_000_000_000_000_000u128,
+                    i * 7,
+                )
+            })
+            .join("\n");
+        assert_eq!(1000, parse_machines(&input).unwrap().len());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |input: &str| {
+            let ParseError {
+                machine,
+                line,
+                reason,
+            } = parse_machines(input).unwrap_err();
+            (machine, line, reason)
+        };
+        let first_machine = "Button A: X+1, Y+2\nButton B: X+3, Y+4\nPrize: X=5, Y=6\n\n";
+
+        assert_eq!(
+            (2, 6, Reason::DuplicateButton("A".to_string())),
+            error(&format!(
+                "{first_machine}Button A: X+1, Y+2\nButton A: X+3, Y+4\nPrize: X=5, Y=6"
+            ))
+        );
+        assert_eq!(
+            (
+                2,
+                5,
+                Reason::MalformedButton("Button A: X=1, Y=2".to_string())
+            ),
+            error(&format!("{first_machine}Button A: X=1, Y=2"))
+        );
+        assert_eq!(
+            (2, 6, Reason::UnknownCost("C".to_string())),
+            error(&format!(
+                "{first_machine}Button A: X+1, Y+2\nButton C: X+3, Y+4\nPrize: X=5, Y=6"
+            ))
+        );
+        // an explicit cost overrides the puzzle's
+        assert_eq!(
+            Ok(vec![Machine::new(
+                vec![Button::new(1, 2, 5)],
+                Prize { x: 5, y: 10 }
+            )]),
+            parse_machines("Button A: X+1, Y+2, Cost 5\nPrize: X=5, Y=10")
+        );
+        assert_eq!(
+            (1, 3, Reason::MalformedPrize("Prize: X=5".to_string())),
+            error("Button A: X+1, Y+2\nButton B: X+3, Y+4\nPrize: X=5")
+        );
+        assert_eq!(
+            (
+                1,
+                1,
+                Reason::NumberOutOfRange("+1000000000000000000000000000000000000000".to_string())
+            ),
+            error("Button A: X+1000000000000000000000000000000000000000, Y+2")
+        );
+        assert_eq!(
+            (2, 5, Reason::NoButtons),
+            error(&format!("{first_machine}Prize: X=0, Y=0"))
+        );
+        assert_eq!(
+            (1, 3, Reason::MissingPrize),
+            error("Button A: X+1, Y+2\nButton B: X+3, Y+4\n\nPrize: X=5, Y=6")
+        );
+        assert_eq!(
+            (2, 8, Reason::ExtraLine("Prize: X=7, Y=8".to_string())),
+            error(&format!(
+                "{first_machine}Button A: X+1, Y+2\nButton B: X+3, Y+4\nPrize: X=5, Y=6\nPrize: X=7, Y=8"
+            ))
+        );
     }
 }
@@ -0,0 +1,64 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Reason {
+    MalformedButton(String),
+    DuplicateButton(String),
+    UnknownCost(String),
+    MalformedPrize(String),
+    NumberOutOfRange(String),
+    NoButtons,
+    MissingPrize,
+    ExtraLine(String),
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Reason::*;
+        match self {
+            MalformedButton(line) => write!(
+                f,
+                "expected a button like 'Button A: X+94, Y-34', found '{line}'"
+            ),
+            DuplicateButton(label) => write!(f, "the machine already has a button {label}"),
+            UnknownCost(label) => write!(
+                f,
+                "only buttons A and B have a known cost, give button {label}'s like 'Cost 2'"
+            ),
+            MalformedPrize(line) => write!(
+                f,
+                "expected a prize like 'Prize: X=8400, Y=5400', found '{line}'"
+            ),
+            NumberOutOfRange(number) => write!(f, "{number} is too big"),
+            NoButtons => write!(f, "the machine needs at least one button before its prize"),
+            MissingPrize => write!(f, "the machine ends before its prize"),
+            ExtraLine(line) => write!(f, "the machine already has a prize, found '{line}'"),
+        }
+    }
+}
+
+pub fn parse_number(number_str: &str) -> Result<i128, Reason> {
+    number_str
+        .parse()
+        .map_err(|_| Reason::NumberOutOfRange(number_str.to_string()))
+}
+
+// machine and line numbers start at 1, like in an editor
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub machine: usize,
+    pub line: usize,
+    pub reason: Reason,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Machine {}, line {}: {}",
+            self.machine, self.line, self.reason
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -1,22 +1,31 @@
+use std::str::FromStr;
+
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use super::parse_error::{parse_number, Reason};
+
 static PRIZE_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^Prize: X=([1-9][0-9]{1,4}), Y=([1-9][0-9]{1,4})$").unwrap());
+    Lazy::new(|| Regex::new(r"^Prize: X=([+-]?\d+), Y=([+-]?\d+)$").unwrap());
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Prize {
-    pub x: i64,
-    pub y: i64,
+    pub x: i128,
+    pub y: i128,
 }
 
-impl From<&str> for Prize {
-    fn from(value: &str) -> Self {
-        let (_, [x_str, y_str]) = PRIZE_REGEX.captures(value).unwrap().extract();
+impl FromStr for Prize {
+    type Err = Reason;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (_, [x_str, y_str]) = PRIZE_REGEX
+            .captures(value)
+            .ok_or_else(|| Reason::MalformedPrize(value.to_string()))?
+            .extract();
 
-        Prize {
-            x: x_str.parse().unwrap(),
-            y: y_str.parse().unwrap(),
-        }
+        Ok(Prize {
+            x: parse_number(x_str)?,
+            y: parse_number(y_str)?,
+        })
     }
 }
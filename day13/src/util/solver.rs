@@ -0,0 +1,284 @@
+use std::fmt::{Display, Formatter};
+
+use num_integer::{ExtendedGcd, Integer};
+
+use super::button::Button;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Error {
+    // no whole, non-negative number of presses lands on the prize
+    Unreachable,
+    // an intermediate value doesn't fit in an i128
+    Overflow,
+    // a button that can move both ways along an axis needs a press limit
+    // before the other buttons can be searched around it
+    Unbounded,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Error::*;
+        match self {
+            Unreachable => write!(f, "The prize can't be reached"),
+            Overflow => write!(f, "The numbers are too big to solve exactly"),
+            Unbounded => write!(
+                f,
+                "A button can be pressed any number of times, so it needs a press limit"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn mul(a: i128, b: i128) -> Result<i128, Error> {
+    a.checked_mul(b).ok_or(Error::Overflow)
+}
+
+fn add(a: i128, b: i128) -> Result<i128, Error> {
+    a.checked_add(b).ok_or(Error::Overflow)
+}
+
+fn sub(a: i128, b: i128) -> Result<i128, Error> {
+    a.checked_sub(b).ok_or(Error::Overflow)
+}
+
+// the determinant of the 2x2 matrix with a and b as its columns
+fn determinant(a: (i128, i128), b: (i128, i128)) -> Result<i128, Error> {
+    sub(mul(a.0, b.1)?, mul(a.1, b.0)?)
+}
+
+// finds whole presses u, v >= 0 with u * a + v * b = target within the
+// buttons' press limits, using the fewest tokens when the buttons are
+// collinear and there's a whole line of answers
+fn solve_two_buttons(a: &Button, b: &Button, target: (i128, i128)) -> Result<(i128, i128), Error> {
+    let (a_step, b_step) = ((a.x, a.y), (b.x, b.y));
+    let det = determinant(a_step, b_step)?;
+
+    if det != 0 {
+        // cramer's rule
+        let u_numerator = determinant(target, b_step)?;
+        let v_numerator = determinant(a_step, target)?;
+        if u_numerator % det != 0 || v_numerator % det != 0 {
+            return Err(Error::Unreachable);
+        }
+        let (u, v) = (u_numerator / det, v_numerator / det);
+        return if a.allows_presses(u) && b.allows_presses(v) {
+            Ok((u, v))
+        } else {
+            Err(Error::Unreachable)
+        };
+    }
+
+    // both buttons move along the same line, so the target has to be on it too
+    if determinant(a_step, target)? != 0 || determinant(b_step, target)? != 0 {
+        return Err(Error::Unreachable);
+    }
+
+    // every vector is a multiple of the same direction, so solving along
+    // whichever axis it moves in solves the other axis as well
+    if a.x != 0 || b.x != 0 || target.0 != 0 {
+        solve_one_dimension((a.x, a), (b.x, b), target.0)
+    } else {
+        solve_one_dimension((a.y, a), (b.y, b), target.1)
+    }
+}
+
+// finds whole u, v >= 0 within the press limits with u * a + v * b = target
+// and the fewest tokens, for non-negative costs
+fn solve_one_dimension(
+    (a, a_button): (i128, &Button),
+    (b, b_button): (i128, &Button),
+    target: i128,
+) -> Result<(i128, i128), Error> {
+    let single_button = |step: i128, button: &Button| match step {
+        0 if target == 0 => Ok(0),
+        0 => Err(Error::Unreachable),
+        _ if target % step == 0 && button.allows_presses(target / step) => Ok(target / step),
+        _ => Err(Error::Unreachable),
+    };
+
+    if a == 0 {
+        return Ok((0, single_button(b, b_button)?));
+    }
+    if b == 0 {
+        return Ok((single_button(a, a_button)?, 0));
+    }
+
+    let ExtendedGcd { gcd, x, y, .. } = a.extended_gcd(&b);
+    if target % gcd != 0 {
+        return Err(Error::Unreachable);
+    }
+
+    // every answer is (u_0 + k * du, v_0 - k * dv) for some whole k
+    let scale = target / gcd;
+    let (u_0, v_0) = (mul(x, scale)?, mul(y, scale)?);
+    let (du, dv) = (b / gcd, a / gcd);
+
+    // u >= 0 and v >= 0 each bound k on one side, and so do the press limits
+    let mut lowest_k = None::<i128>;
+    let mut highest_k = None::<i128>;
+    let mut bound = |value: i128, step: i128| {
+        // value + k * step >= 0
+        if step > 0 {
+            let k = Integer::div_ceil(&-value, &step);
+            lowest_k = Some(lowest_k.map_or(k, |lowest| lowest.max(k)));
+        } else {
+            let k = Integer::div_floor(&value, &-step);
+            highest_k = Some(highest_k.map_or(k, |highest| highest.min(k)));
+        }
+    };
+    bound(u_0, du);
+    bound(v_0, -dv);
+    if let Some(max_presses) = a_button.max_presses {
+        bound(sub(max_presses, u_0)?, -du);
+    }
+    if let Some(max_presses) = b_button.max_presses {
+        bound(sub(max_presses, v_0)?, dv);
+    }
+
+    // the cost goes up by this much for each step in k
+    let slope = sub(mul(a_button.cost, du)?, mul(b_button.cost, dv)?)?;
+
+    let k = match (lowest_k, highest_k) {
+        (Some(lowest), Some(highest)) if lowest > highest => return Err(Error::Unreachable),
+        (Some(lowest), Some(highest)) => {
+            if slope > 0 {
+                lowest
+            } else {
+                highest
+            }
+        }
+        // with non-negative costs, the cost can only fall towards the
+        // bounded end, or stay flat
+        (Some(k), None) | (None, Some(k)) => k,
+        (None, None) => unreachable!("u and v can't both be unbounded"),
+    };
+
+    Ok((add(u_0, mul(k, du)?)?, sub(v_0, mul(k, dv)?)?))
+}
+
+// the most times a button could usefully be pressed: its press limit, or how
+// far it can go along an axis that no button ever moves backwards on
+fn press_bound(button: &Button, buttons: &[Button], target: (i128, i128)) -> Option<i128> {
+    if button.x == 0 && button.y == 0 {
+        return Some(0);
+    }
+
+    let axis_bound = |axis: fn(&Button) -> i128, target: i128| {
+        let step = axis(button);
+        (step > 0 && buttons.iter().all(|button| axis(button) >= 0))
+            .then(|| (target / step).max(-1))
+    };
+
+    [
+        button.max_presses,
+        axis_bound(|button| button.x, target.0),
+        axis_bound(|button| button.y, target.1),
+    ]
+    .into_iter()
+    .flatten()
+    .min()
+}
+
+struct Search<'a> {
+    buttons: &'a [Button],
+    pair: (Button, Button),
+    // indexes of the buttons to try every number of presses of, and how many
+    searched: Vec<(usize, i128)>,
+    presses: Vec<i128>,
+    best: Option<(i128, Vec<i128>)>,
+}
+
+impl Search<'_> {
+    fn search(&mut self, depth: usize, target: (i128, i128), cost: i128) -> Result<(), Error> {
+        let Some(&(index, max_presses)) = self.searched.get(depth) else {
+            let (u, v) = match solve_two_buttons(&self.pair.0, &self.pair.1, target) {
+                Err(Error::Unreachable) => return Ok(()),
+                result => result?,
+            };
+            let cost = add(
+                cost,
+                add(mul(u, self.pair.0.cost)?, mul(v, self.pair.1.cost)?)?,
+            )?;
+            if self.best.as_ref().is_none_or(|(best, _)| cost < *best) {
+                let mut presses = self.presses.clone();
+                presses.extend([u, v]);
+                self.best = Some((cost, presses));
+            }
+            return Ok(());
+        };
+
+        let button = &self.buttons[index];
+        for presses in 0..=max_presses {
+            let cost = add(cost, mul(presses, button.cost)?)?;
+            // pressing more only costs more
+            if self.best.as_ref().is_some_and(|(best, _)| cost >= *best) {
+                break;
+            }
+            let target = (
+                sub(target.0, mul(presses, button.x)?)?,
+                sub(target.1, mul(presses, button.y)?)?,
+            );
+            self.presses[depth] = presses;
+            self.search(depth + 1, target, cost)?;
+        }
+
+        Ok(())
+    }
+}
+
+// the integer program: minimise the total cost of the presses, so that the
+// presses land exactly on the target, with every button pressed a whole,
+// non-negative number of times no more than its limit. The two buttons with
+// the most room are solved exactly for each combination of presses of the
+// others, so only those others need to be small.
+pub fn cheapest_presses(buttons: &[Button], target: (i128, i128)) -> Result<Vec<i128>, Error> {
+    debug_assert!(buttons.iter().all(|button| button.cost >= 0));
+
+    let mut order = buttons
+        .iter()
+        .enumerate()
+        .map(|(index, button)| (index, press_bound(button, buttons, target)))
+        .collect::<Vec<_>>();
+    order.sort_by_key(|&(_, bound)| std::cmp::Reverse(bound.unwrap_or(i128::MAX)));
+
+    // missing buttons in the pair never get pressed
+    let no_button = Button::new(0, 0, 0);
+    let mut pair = order
+        .iter()
+        .take(2)
+        .map(|&(index, _)| buttons[index].clone());
+    let pair = (
+        pair.next().unwrap_or(no_button.clone()),
+        pair.next().unwrap_or(no_button),
+    );
+
+    let searched = order
+        .iter()
+        .skip(2)
+        .map(|&(index, bound)| bound.map(|bound| (index, bound)).ok_or(Error::Unbounded))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut search = Search {
+        buttons,
+        pair,
+        presses: vec![0; searched.len()],
+        searched,
+        best: None,
+    };
+    search.search(0, target, 0)?;
+    let (_, presses_in_order) = search.best.ok_or(Error::Unreachable)?;
+
+    // put the presses back in the buttons' order
+    let mut presses = vec![0; buttons.len()];
+    let order_of_presses = search
+        .searched
+        .iter()
+        .map(|&(index, _)| index)
+        .chain(order.iter().take(2).map(|&(index, _)| index));
+    for (index, count) in order_of_presses.zip(presses_in_order) {
+        presses[index] = count;
+    }
+    Ok(presses)
+}
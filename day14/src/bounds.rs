@@ -1,5 +1,37 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub struct Bounds {
     pub num_rows: usize,
     pub num_columns: usize,
 }
+
+impl Bounds {
+    pub const EXAMPLE: Bounds = Bounds {
+        num_rows: 7,
+        num_columns: 11,
+    };
+
+    pub const PUZZLE: Bounds = Bounds {
+        num_rows: 103,
+        num_columns: 101,
+    };
+
+    // robots that all fit in the example's room are taken to be the example,
+    // anything else gets the puzzle's room, grown until every robot fits
+    pub fn infer<'a>(positions: impl IntoIterator<Item = &'a (usize, usize)>) -> Bounds {
+        let (num_rows, num_columns) =
+            positions
+                .into_iter()
+                .fold((0, 0), |(num_rows, num_columns), &(row, column)| {
+                    (num_rows.max(row + 1), num_columns.max(column + 1))
+                });
+
+        if num_rows <= Bounds::EXAMPLE.num_rows && num_columns <= Bounds::EXAMPLE.num_columns {
+            Bounds::EXAMPLE
+        } else {
+            Bounds {
+                num_rows: num_rows.max(Bounds::PUZZLE.num_rows),
+                num_columns: num_columns.max(Bounds::PUZZLE.num_columns),
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    fmt::{Display, Formatter},
+    path::PathBuf,
+};
+
+use crate::frames::Shading;
+
+pub struct Export {
+    pub directory: PathBuf,
+    pub first_step: usize,
+    // defaults to a whole cycle of the room, after which every frame repeats
+    pub end_step: Option<usize>,
+    pub shading: Shading,
+    pub as_strip: bool,
+}
+
+#[derive(Default)]
+pub struct Config {
+    // the puzzle input when not given
+    pub input: Option<PathBuf>,
+    // inferred from the robots when not given
+    pub num_rows: Option<usize>,
+    pub num_columns: Option<usize>,
+    // print the room and its statistics at the picture step
+    pub show_picture: bool,
+    pub export: Option<Export>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    MissingValue(String),
+    InvalidValue(String, String),
+    UnknownArgument(String),
+    ExportOptionWithoutExport(String),
+    EmptyRoom,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Error::*;
+        match self {
+            MissingValue(flag) => write!(f, "No value given for '{flag}'"),
+            InvalidValue(flag, value) => {
+                write!(
+                    f,
+                    "Expected a non-negative integer for '{flag}', got '{value}'"
+                )
+            }
+            UnknownArgument(arg) => write!(
+                f,
+                "Unknown argument '{arg}', expected --input FILE, --rows N, --columns N, \
+                 --picture, --export DIR, --from N, --to N, --heatmap or --strip"
+            ),
+            ExportOptionWithoutExport(arg) => {
+                write!(f, "'{arg}' only makes sense along with --export DIR")
+            }
+            EmptyRoom => write!(f, "The room needs at least one row and one column"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Config {
+    // --input FILE reads the robots from FILE, --rows N and --columns N set
+    // the size of the room, --picture prints the room at the picture step.
+    // --export DIR writes frames into DIR, from step --from N up to but not
+    // including step --to N, shaded by robot density with --heatmap and as
+    // one tall image with --strip
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Config, Error> {
+        let mut directory = None;
+        let mut first_step = 0;
+        let mut end_step = None;
+        let mut shading = Shading::Occupancy;
+        let mut as_strip = false;
+        let mut export_options = Vec::new();
+        let mut config = Config::default();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| Error::MissingValue(arg.clone()));
+            let parse = |value: String| {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| Error::InvalidValue(arg.clone(), value))
+            };
+
+            match arg.as_str() {
+                "--input" => {
+                    config.input = Some(PathBuf::from(value()?));
+                    continue;
+                }
+                "--rows" => {
+                    config.num_rows = Some(parse(value()?)?);
+                    continue;
+                }
+                "--columns" => {
+                    config.num_columns = Some(parse(value()?)?);
+                    continue;
+                }
+                "--picture" => {
+                    config.show_picture = true;
+                    continue;
+                }
+                "--export" => {
+                    directory = Some(PathBuf::from(value()?));
+                    continue;
+                }
+                "--from" => first_step = parse(value()?)?,
+                "--to" => end_step = Some(parse(value()?)?),
+                "--heatmap" => shading = Shading::Density,
+                "--strip" => as_strip = true,
+                _ => return Err(Error::UnknownArgument(arg)),
+            }
+            export_options.push(arg);
+        }
+
+        if config.num_rows == Some(0) || config.num_columns == Some(0) {
+            return Err(Error::EmptyRoom);
+        }
+
+        config.export = match directory {
+            Some(directory) => Some(Export {
+                directory,
+                first_step,
+                end_step,
+                shading,
+                as_strip,
+            }),
+            None => match export_options.into_iter().next() {
+                Some(arg) => return Err(Error::ExportOptionWithoutExport(arg)),
+                None => None,
+            },
+        };
+
+        Ok(config)
+    }
+}
@@ -0,0 +1,127 @@
+use std::{fs, io, ops::Range, path::Path};
+
+use crate::puzzle_input::PuzzleInput;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shading {
+    // black where there's a robot, saved as PBM
+    Occupancy,
+    // darker where more robots overlap, saved as PGM
+    Density,
+}
+
+impl Shading {
+    fn extension(&self) -> &'static str {
+        match self {
+            Shading::Occupancy => "pbm",
+            Shading::Density => "pgm",
+        }
+    }
+}
+
+// one level per pixel, row by row. With occupancy shading the levels are 1
+// for a robot and 0 for the floor, with density shading 0 is the most
+// crowded position and 255 the floor
+pub struct Image {
+    shading: Shading,
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn frame(puzzle_input: &PuzzleInput, shading: Shading) -> Image {
+        let bounds = puzzle_input.bounds();
+        let counts = puzzle_input.robot_counts();
+        let max_count = counts.values().copied().max().unwrap_or(1);
+
+        let pixels = (0..bounds.num_rows)
+            .flat_map(|row| (0..bounds.num_columns).map(move |column| (row, column)))
+            .map(|position| {
+                let count = counts.get(&position).copied().unwrap_or(0);
+                match shading {
+                    Shading::Occupancy => (count > 0) as u8,
+                    Shading::Density => (255 - count * 255 / max_count) as u8,
+                }
+            })
+            .collect();
+
+        Image {
+            shading,
+            width: bounds.num_columns,
+            height: bounds.num_rows,
+            pixels,
+        }
+    }
+
+    // stacks the frames top to bottom, they all need the same shading and width
+    pub fn strip(frames: impl IntoIterator<Item = Image>) -> Option<Image> {
+        let mut frames = frames.into_iter();
+        let mut strip = frames.next()?;
+
+        for frame in frames {
+            assert!(frame.shading == strip.shading && frame.width == strip.width);
+            strip.height += frame.height;
+            strip.pixels.extend(frame.pixels);
+        }
+
+        Some(strip)
+    }
+
+    // binary netpbm, P4 for occupancy and P5 for density
+    pub fn to_netpbm(&self) -> Vec<u8> {
+        match self.shading {
+            Shading::Occupancy => {
+                let mut bytes = format!("P4\n{} {}\n", self.width, self.height).into_bytes();
+                // each row is padded to a whole byte, first pixel in the top bit
+                for row in self.pixels.chunks(self.width) {
+                    bytes.extend(row.chunks(8).map(|bits| {
+                        bits.iter()
+                            .enumerate()
+                            .fold(0u8, |byte, (i, &bit)| byte | (bit << (7 - i)))
+                    }));
+                }
+                bytes
+            }
+            Shading::Density => {
+                let mut bytes = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+                bytes.extend(&self.pixels);
+                bytes
+            }
+        }
+    }
+}
+
+// writes a file per step, named after the step, or a single strip of them all
+pub fn export_frames(
+    puzzle_input: &PuzzleInput,
+    steps: Range<usize>,
+    shading: Shading,
+    as_strip: bool,
+    directory: &Path,
+) -> io::Result<()> {
+    fs::create_dir_all(directory)?;
+
+    let frames = steps
+        .clone()
+        .map(|step| (step, Image::frame(&puzzle_input.after(step), shading)));
+
+    if as_strip {
+        let Some(strip) = Image::strip(frames.map(|(_, frame)| frame)) else {
+            return Ok(());
+        };
+        let file_name = format!(
+            "steps_{:05}_to_{:05}.{}",
+            steps.start,
+            steps.end,
+            shading.extension()
+        );
+        fs::write(directory.join(file_name), strip.to_netpbm())
+    } else {
+        for (step, frame) in frames {
+            let file_name = format!("step_{step:05}.{}", shading.extension());
+            fs::write(directory.join(file_name), frame.to_netpbm())?;
+        }
+        Ok(())
+    }
+}
@@ -1,62 +1,84 @@
 mod bounds;
+mod config;
+mod frames;
+mod picture;
 mod puzzle_input;
 mod quadrant;
 mod robot;
+mod stats;
 
-use enum_iterator::cardinality;
-
-use puzzle_input::PuzzleInput;
-use quadrant::Quadrant;
-use robot::Robot;
+use bounds::Bounds;
+use config::{Config, Export};
+use frames::export_frames;
+use picture::{find_picture_step, MismatchedSteps};
+use puzzle_input::{parse_robots, PuzzleInput};
+use stats::Statistics;
 
 fn part1(puzzle_input: &PuzzleInput) -> usize {
     const NUM_STEPS: usize = 100;
 
-    let mut counts_by_quadrant: [usize; cardinality::<Quadrant>()] = [0; cardinality::<Quadrant>()];
+    Statistics::after(puzzle_input, NUM_STEPS).safety_factor()
+}
 
-    for robot in puzzle_input.robots() {
-        let mut robot = robot.clone();
+fn part2(puzzle_input: &PuzzleInput) -> Result<usize, MismatchedSteps> {
+    find_picture_step(puzzle_input)
+}
 
-        for _ in 0..NUM_STEPS {
-            robot.step(puzzle_input.bounds());
-        }
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::from_args(std::env::args().skip(1))?;
 
-        if let Some(quadrant) = robot.get_quadrant(puzzle_input.bounds()) {
-            counts_by_quadrant[quadrant as usize] += 1;
-        }
-    }
+    let file_contents = match &config.input {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => include_str!("../input.txt").to_string(),
+    };
 
-    counts_by_quadrant.into_iter().product()
-}
+    let robots = parse_robots(&file_contents)?;
+    let inferred = Bounds::infer(robots.iter().map(|robot| &robot.position));
+    let num_rows = config.num_rows.unwrap_or(inferred.num_rows);
+    let num_columns = config.num_columns.unwrap_or(inferred.num_columns);
+    let puzzle_input = PuzzleInput::new(num_rows, num_columns, robots)?;
 
-fn part2(mut puzzle_input: PuzzleInput) {
-    const NUM_STEPS: usize = 6752;
-    let bounds = puzzle_input.bounds().clone();
+    println!("{}", part1(&puzzle_input));
 
-    for _ in 0..NUM_STEPS {
-        for robot in puzzle_input.robots_mut() {
-            robot.step(&bounds);
+    match part2(&puzzle_input) {
+        Ok(num_steps) => {
+            println!("{num_steps}");
+            if config.show_picture {
+                println!("{}", puzzle_input.after(num_steps).board_as_str());
+                println!("{:?}", Statistics::after(&puzzle_input, num_steps));
+            }
         }
+        Err(error) => println!("No picture found: {error}"),
     }
 
-    println!("{}", puzzle_input.board_as_str());
-}
-
-fn main() {
-    let file_contents_as_str = include_str!("../input.txt");
-
-    let num_rows = 103;
-    let num_columns = 101;
-    let robots = file_contents_as_str.lines().map(Robot::from).collect();
-    let puzzle_input = PuzzleInput::new(num_rows, num_columns, robots);
+    if let Some(Export {
+        directory,
+        first_step,
+        end_step,
+        shading,
+        as_strip,
+    }) = config.export
+    {
+        let bounds = puzzle_input.bounds();
+        let end_step = end_step.unwrap_or(bounds.num_rows * bounds.num_columns);
+        export_frames(
+            &puzzle_input,
+            first_step..end_step,
+            shading,
+            as_strip,
+            &directory,
+        )?;
+        println!("Wrote frames to {}", directory.display());
+    }
 
-    println!("{}", part1(&puzzle_input));
-    part2(puzzle_input);
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use frames::{Image, Shading};
+    use robot::{ParseError, Robot};
 
     const TEST_INPUT: &str = "p=0,4 v=3,-3\n\
                               p=6,3 v=-1,-3\n\
@@ -75,8 +97,107 @@ mod tests {
     fn test_part1() {
         let num_rows = 7;
         let num_columns = 11;
-        let robots = TEST_INPUT.lines().map(Robot::from).collect();
-        let puzzle_input = PuzzleInput::new(num_rows, num_columns, robots);
-        assert_eq!(12, part1(&puzzle_input))
+        let robots = parse_robots(TEST_INPUT).unwrap();
+        let puzzle_input = PuzzleInput::new(num_rows, num_columns, robots).unwrap();
+        assert_eq!(12, part1(&puzzle_input));
+
+        // p=10,3 is the eleventh column, which a ten column room doesn't have
+        let robots = parse_robots(TEST_INPUT).unwrap();
+        let error = PuzzleInput::new(num_rows, 10, robots).err().unwrap();
+        assert_eq!(
+            "Robot 3 starts at p=10,3, outside a room of 7 rows and 10 columns",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_part2() {
+        // every robot meets in the same place after the given step, which
+        // only happens again a whole cycle later: 77 steps in a 7 by 11 room,
+        // but only 30 in a 6 by 10 room since 6 and 10 share a factor of 2
+        for (num_rows, num_columns, meeting_step) in [(7, 11, 40), (6, 10, 23)] {
+            let robots = (-3..=3)
+                .flat_map(|v0| (-3..=3).map(move |v1| (v0, v1)))
+                .map(|(v0, v1)| Robot {
+                    position: (
+                        (3 - v0 * meeting_step as isize).rem_euclid(num_rows as isize) as usize,
+                        (5 - v1 * meeting_step as isize).rem_euclid(num_columns as isize) as usize,
+                    ),
+                    velocity: (v0, v1),
+                })
+                .collect();
+            let puzzle_input = PuzzleInput::new(num_rows, num_columns, robots).unwrap();
+            assert_eq!(Ok(meeting_step), part2(&puzzle_input));
+            assert!(puzzle_input
+                .after(meeting_step)
+                .robots()
+                .iter()
+                .all(|robot| robot.position == (3, 5)));
+        }
+    }
+
+    #[test]
+    fn test_frames() {
+        let robots = parse_robots(TEST_INPUT).unwrap();
+        let puzzle_input = PuzzleInput::new(7, 11, robots).unwrap();
+
+        // 11 columns pack into two bytes a row, p=0,0 and p=2,0 and p=3,0 on the top row
+        let pbm = Image::frame(&puzzle_input, Shading::Occupancy).to_netpbm();
+        let header = b"P4\n11 7\n";
+        assert_eq!(header.len() + 7 * 2, pbm.len());
+        assert_eq!(&pbm[..header.len()], header);
+        assert_eq!([0b10110000, 0], pbm[header.len()..header.len() + 2]);
+
+        // two robots start on p=3,0, so they're the darkest
+        let pgm = Image::frame(&puzzle_input, Shading::Density).to_netpbm();
+        let header = b"P5\n11 7\n255\n";
+        assert_eq!(header.len() + 7 * 11, pgm.len());
+        assert_eq!([128, 255, 128, 0], pgm[header.len()..header.len() + 4]);
+
+        let strip = Image::strip(
+            (0..3).map(|step| Image::frame(&puzzle_input.after(step), Shading::Density)),
+        )
+        .unwrap()
+        .to_netpbm();
+        assert!(strip.starts_with(b"P5\n11 21\n255\n"));
+    }
+
+    #[test]
+    fn test_statistics() {
+        let robots = parse_robots(TEST_INPUT).unwrap();
+        let bounds = Bounds::infer(robots.iter().map(|robot| &robot.position));
+        assert_eq!((7, 11), (bounds.num_rows, bounds.num_columns));
+
+        let puzzle_input = PuzzleInput::new(bounds.num_rows, bounds.num_columns, robots).unwrap();
+        let statistics = Statistics::after(&puzzle_input, 100);
+        // top left, bottom left, bottom right, top right
+        assert_eq!([1, 4, 1, 3], statistics.counts_by_quadrant);
+        assert_eq!(3, statistics.on_centre_lines);
+        assert_eq!(12, statistics.safety_factor());
+
+        let robot: Robot = "p=123456789,1000 v=-987654321,5".parse().unwrap();
+        assert_eq!((1000, 123456789), robot.position);
+
+        // too big for a usize, even though it's all digits
+        let too_big = "p=99999999999999999999999,0 v=1,1";
+        assert_eq!(
+            Some(ParseError::NumberOutOfRange(
+                "99999999999999999999999".to_string()
+            )),
+            too_big.parse::<Robot>().err()
+        );
+        assert_eq!(
+            "Line 2: expected a robot like 'p=0,4 v=3,-3', found 'p=1,2'",
+            parse_robots("p=0,0 v=1,1\np=1,2")
+                .err()
+                .unwrap()
+                .to_string()
+        );
+        let bounds = Bounds::infer([&robot.position]);
+        assert_eq!((1001, 123456790), (bounds.num_rows, bounds.num_columns));
+
+        // a robot just outside the example's room gets the puzzle's room
+        let bounds = Bounds::infer([&(7, 0)]);
+        assert_eq!((103, 101), (bounds.num_rows, bounds.num_columns));
     }
 }
@@ -0,0 +1,117 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{bounds::Bounds, puzzle_input::PuzzleInput};
+
+// the rows and columns are tightest at steps that can never happen together,
+// since both axes repeat every common_period steps
+#[derive(Debug, PartialEq, Eq)]
+pub struct MismatchedSteps {
+    pub row_step: usize,
+    pub column_step: usize,
+    pub common_period: usize,
+}
+
+impl Display for MismatchedSteps {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The rows are tightest after {} steps and the columns after {}, \
+             which never line up since both repeat every {} steps",
+            self.row_step, self.column_step, self.common_period
+        )
+    }
+}
+
+impl std::error::Error for MismatchedSteps {}
+
+// how spread out the robots are along one axis after the given number of
+// steps, as the count squared times the variance so it stays a whole number
+pub fn spread(values: impl Iterator<Item = usize>) -> u128 {
+    let (count, sum, sum_of_squares) = values.fold((0u128, 0u128, 0u128), |(n, s, ss), value| {
+        let value = value as u128;
+        (n + 1, s + value, ss + value * value)
+    });
+    count * sum_of_squares - sum * sum
+}
+
+// the first step in 0..period with the least spread
+fn tightest_step(period: usize, spread_after: impl Fn(usize) -> u128) -> usize {
+    (0..period).min_by_key(|&step| spread_after(step)).unwrap()
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// the smallest step that is step_a mod period_a and step_b mod period_b.
+// The two have to agree mod the gcd of the periods, and then there's exactly
+// one such step in every lcm of the periods.
+fn combine_steps(
+    step_a: usize,
+    period_a: usize,
+    step_b: usize,
+    period_b: usize,
+) -> Result<usize, usize> {
+    let common_period = gcd(period_a, period_b);
+    if step_a % common_period != step_b % common_period {
+        return Err(common_period);
+    }
+
+    let lcm = period_a / common_period * period_b;
+    Ok((step_a..lcm)
+        .step_by(period_a)
+        .find(|step| step % period_b == step_b)
+        .unwrap())
+}
+
+// rows repeat every num_rows steps and columns every num_columns steps, so
+// the picture is where each axis is at its tightest. The two are found
+// separately and combined with the chinese remainder theorem.
+pub fn find_picture_step(puzzle_input: &PuzzleInput) -> Result<usize, MismatchedSteps> {
+    let Bounds {
+        num_rows,
+        num_columns,
+    } = *puzzle_input.bounds();
+    let robots = puzzle_input.robots();
+
+    let row_step = tightest_step(num_rows, |step| {
+        spread(
+            robots
+                .iter()
+                .map(|robot| robot.position_after(step, puzzle_input.bounds()).0),
+        )
+    });
+    let column_step = tightest_step(num_columns, |step| {
+        spread(
+            robots
+                .iter()
+                .map(|robot| robot.position_after(step, puzzle_input.bounds()).1),
+        )
+    });
+
+    combine_steps(row_step, num_rows, column_step, num_columns).map_err(|common_period| {
+        MismatchedSteps {
+            row_step,
+            column_step,
+            common_period,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine_steps() {
+        assert_eq!(Ok(6752), combine_steps(6752 % 103, 103, 6752 % 101, 101));
+        // 4 and 6 share a factor of 2, so the steps repeat every 12
+        assert_eq!(Ok(10), combine_steps(2, 4, 4, 6));
+        assert_eq!(Ok(3), combine_steps(3, 6, 3, 6));
+        assert_eq!(Err(2), combine_steps(1, 4, 2, 6));
+    }
+}
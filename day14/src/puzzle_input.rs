@@ -1,28 +1,69 @@
-use std::{char, collections::HashMap, iter::once};
+use std::{
+    char,
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    iter::once,
+};
 
-use crate::{bounds::Bounds, robot::Robot};
+use crate::{
+    bounds::Bounds,
+    robot::{ParseError, Robot},
+};
 
 pub struct PuzzleInput {
     bounds: Bounds,
     robots: Vec<Robot>,
 }
 
-impl PuzzleInput {
-    pub fn new(num_rows: usize, num_columns: usize, robots: Vec<Robot>) -> Self {
-        for robot in robots.iter() {
-            assert!(
-                robot.position.0 < num_rows && robot.position.1 < num_columns,
-                "robot.position = {:?}",
-                robot.position
-            );
+#[derive(Debug)]
+pub enum Error {
+    // robots are numbered from 1, like the lines of the input
+    InvalidRobot(usize, ParseError),
+    RobotOutsideRoom(usize, Robot, Bounds),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Error::*;
+        match self {
+            InvalidRobot(number, error) => write!(f, "Line {number}: {error}"),
+            RobotOutsideRoom(number, robot, bounds) => write!(
+                f,
+                "Robot {number} starts at p={},{}, outside a room of {} rows and {} columns",
+                robot.position.1, robot.position.0, bounds.num_rows, bounds.num_columns
+            ),
         }
-        Self {
-            bounds: Bounds {
-                num_rows,
-                num_columns,
-            },
-            robots,
+    }
+}
+
+impl std::error::Error for Error {}
+
+// one robot per line
+pub fn parse_robots(input: &str) -> Result<Vec<Robot>, Error> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|error| Error::InvalidRobot(i + 1, error))
+        })
+        .collect()
+}
+
+impl PuzzleInput {
+    pub fn new(num_rows: usize, num_columns: usize, robots: Vec<Robot>) -> Result<Self, Error> {
+        let bounds = Bounds {
+            num_rows,
+            num_columns,
+        };
+        if let Some((i, robot)) = robots
+            .iter()
+            .enumerate()
+            .find(|(_, robot)| robot.position.0 >= num_rows || robot.position.1 >= num_columns)
+        {
+            return Err(Error::RobotOutsideRoom(i + 1, robot.clone(), bounds));
         }
+        Ok(Self { bounds, robots })
     }
 
     pub fn bounds(&self) -> &Bounds {
@@ -33,16 +74,28 @@ impl PuzzleInput {
         &self.robots
     }
 
-    pub fn robots_mut(&mut self) -> &mut Vec<Robot> {
-        &mut self.robots
+    pub fn after(&self, num_steps: usize) -> PuzzleInput {
+        PuzzleInput {
+            bounds: self.bounds,
+            robots: self
+                .robots
+                .iter()
+                .map(|robot| robot.after(num_steps, &self.bounds))
+                .collect(),
+        }
     }
 
-    pub fn board_as_str(&self) -> String {
+    // how many robots are on each occupied position
+    pub fn robot_counts(&self) -> HashMap<(usize, usize), u32> {
         let mut num_robots = HashMap::new();
         for robot in self.robots.iter() {
             *num_robots.entry(robot.position).or_insert(0) += 1;
         }
-        let num_robots = num_robots;
+        num_robots
+    }
+
+    pub fn board_as_str(&self) -> String {
+        let num_robots = self.robot_counts();
         let ref_num_robots = &num_robots;
 
         (0..self.bounds.num_rows)
@@ -1,3 +1,8 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
 use once_cell::sync::Lazy;
 use regex::Regex;
 
@@ -9,28 +14,67 @@ pub struct Robot {
     pub velocity: (isize, isize),
 }
 
-impl From<&str> for Robot {
-    fn from(value: &str) -> Self {
-        static ROBOT_REGEX: Lazy<Regex> = Lazy::new(|| {
-            Regex::new(r"^p=([0-9]{1,3}),([0-9]{1,3}) v=(-?[0-9]{1,3}),(-?[0-9]{1,3})$").unwrap()
-        });
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    Malformed(String),
+    NumberOutOfRange(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use ParseError::*;
+        match self {
+            Malformed(line) => write!(f, "expected a robot like 'p=0,4 v=3,-3', found '{line}'"),
+            NumberOutOfRange(number) => write!(f, "{number} is too big"),
+        }
+    }
+}
+
+impl FromStr for Robot {
+    type Err = ParseError;
 
-        let (_, [p1, p0, v1, v0]) = ROBOT_REGEX.captures(value).expect(value).extract();
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        static ROBOT_REGEX: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"^p=([0-9]+),([0-9]+) v=(-?[0-9]+),(-?[0-9]+)$").unwrap());
 
-        Robot {
-            position: (p0.parse().unwrap(), p1.parse().unwrap()),
-            velocity: (v0.parse().unwrap(), v1.parse().unwrap()),
+        let (_, [p1, p0, v1, v0]) = ROBOT_REGEX
+            .captures(value)
+            .ok_or_else(|| ParseError::Malformed(value.to_string()))?
+            .extract();
+
+        fn parse<N: FromStr>(number_str: &str) -> Result<N, ParseError> {
+            number_str
+                .parse()
+                .map_err(|_| ParseError::NumberOutOfRange(number_str.to_string()))
         }
+
+        Ok(Robot {
+            position: (parse(p0)?, parse(p1)?),
+            velocity: (parse(v0)?, parse(v1)?),
+        })
     }
 }
 
 impl Robot {
-    pub fn step(&mut self, bounds: &Bounds) {
-        self.position.0 = (self.position.0 + bounds.num_rows).wrapping_add_signed(self.velocity.0)
-            % bounds.num_rows;
-        self.position.1 = (self.position.1 + bounds.num_columns)
-            .wrapping_add_signed(self.velocity.1)
-            % bounds.num_columns;
+    // where the robot is after the given number of seconds, wrapping around
+    // the edges of the room
+    pub fn position_after(&self, num_steps: usize, bounds: &Bounds) -> (usize, usize) {
+        let wrap = |position: usize, velocity: isize, size: usize| {
+            (position as i128 + velocity as i128 * num_steps as i128).rem_euclid(size as i128)
+                as usize
+        };
+
+        (
+            wrap(self.position.0, self.velocity.0, bounds.num_rows),
+            wrap(self.position.1, self.velocity.1, bounds.num_columns),
+        )
+    }
+
+    pub fn after(&self, num_steps: usize, bounds: &Bounds) -> Robot {
+        Robot {
+            position: self.position_after(num_steps, bounds),
+            velocity: self.velocity,
+        }
     }
 
     pub fn get_quadrant(&self, bounds: &Bounds) -> Option<Quadrant> {
@@ -39,13 +83,13 @@ impl Robot {
         {
             None
         } else {
-            let is_left = self.position.0 < bounds.num_rows / 2;
-            let is_top = self.position.1 < bounds.num_columns / 2;
+            let is_top = self.position.0 < bounds.num_rows / 2;
+            let is_left = self.position.1 < bounds.num_columns / 2;
 
-            let quadrant = match (is_left, is_top) {
+            let quadrant = match (is_top, is_left) {
                 (true, true) => Quadrant::TopLeft,
-                (true, false) => Quadrant::BottomLeft,
-                (false, true) => Quadrant::TopRight,
+                (true, false) => Quadrant::TopRight,
+                (false, true) => Quadrant::BottomLeft,
                 (false, false) => Quadrant::BottomRight,
             };
 
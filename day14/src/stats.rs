@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use enum_iterator::cardinality;
+
+use crate::{picture::spread, puzzle_input::PuzzleInput, quadrant::Quadrant};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    pub counts_by_quadrant: [usize; cardinality::<Quadrant>()],
+    // robots on the middle row or column, which aren't in any quadrant
+    pub on_centre_lines: usize,
+    pub row_variance: f64,
+    pub column_variance: f64,
+    // robots with another robot next to them, including diagonally
+    pub with_neighbour: usize,
+}
+
+impl Statistics {
+    pub fn after(puzzle_input: &PuzzleInput, num_steps: usize) -> Statistics {
+        let puzzle_input = puzzle_input.after(num_steps);
+        let bounds = puzzle_input.bounds();
+        let robots = puzzle_input.robots();
+
+        let mut counts_by_quadrant = [0; cardinality::<Quadrant>()];
+        let mut on_centre_lines = 0;
+        for robot in robots {
+            match robot.get_quadrant(bounds) {
+                Some(quadrant) => counts_by_quadrant[quadrant as usize] += 1,
+                None => on_centre_lines += 1,
+            }
+        }
+
+        let variance = |values: Vec<usize>| {
+            let count = values.len().max(1) as f64;
+            spread(values.into_iter()) as f64 / (count * count)
+        };
+
+        let positions = robots
+            .iter()
+            .map(|robot| robot.position)
+            .collect::<HashSet<_>>();
+        let with_neighbour = robots
+            .iter()
+            .filter(|robot| {
+                let (row, column) = robot.position;
+                (row.saturating_sub(1)..=row + 1)
+                    .flat_map(|r| (column.saturating_sub(1)..=column + 1).map(move |c| (r, c)))
+                    .any(|neighbour| neighbour != robot.position && positions.contains(&neighbour))
+            })
+            .count();
+
+        Statistics {
+            counts_by_quadrant,
+            on_centre_lines,
+            row_variance: variance(robots.iter().map(|robot| robot.position.0).collect()),
+            column_variance: variance(robots.iter().map(|robot| robot.position.1).collect()),
+            with_neighbour,
+        }
+    }
+
+    pub fn safety_factor(&self) -> usize {
+        self.counts_by_quadrant.iter().product()
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+};
+
+use array2d::Array2D;
+
+use crate::{direction::Direction, part1_cell::Part1Cell};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Wall,
+    // the id of the box covering the cell
+    Box(usize),
+}
+
+pub fn move_once_in_direction((i, j): (usize, usize), direction: Direction) -> (usize, usize) {
+    // no overflow or underflow, parse_input checks walls are around the whole thang
+    match direction {
+        Direction::North => (i - 1, j),
+        Direction::East => (i, j + 1),
+        Direction::South => (i + 1, j),
+        Direction::West => (i, j - 1),
+    }
+}
+
+// something a move left broken, found by comparing with how the grid started
+#[derive(Debug, PartialEq, Eq)]
+pub enum InvariantError {
+    BoxCountChanged(usize, usize),
+    // a box's cells aren't the same shape they started as, like a wide box
+    // split into halves
+    BoxShapeChanged(usize),
+    // a cell the box covers isn't marked with its id
+    BoxCellNotMarked(usize, (usize, usize)),
+    // a cell is marked with the id of a box that doesn't cover it
+    StrayBoxCell(usize, (usize, usize)),
+    WallChanged((usize, usize)),
+    RobotNotOnEmptyCell((usize, usize)),
+}
+
+impl Display for InvariantError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use InvariantError::*;
+        match self {
+            BoxCountChanged(before, after) => {
+                write!(f, "There were {before} boxes, now there are {after}")
+            }
+            BoxShapeChanged(id) => write!(f, "Box {id} has changed shape"),
+            BoxCellNotMarked(id, position) => {
+                write!(
+                    f,
+                    "Box {id} covers {position:?}, but the grid doesn't say so"
+                )
+            }
+            StrayBoxCell(id, position) => write!(
+                f,
+                "The grid says box {id} covers {position:?}, but it doesn't"
+            ),
+            WallChanged(position) => write!(f, "The wall at {position:?} has changed"),
+            RobotNotOnEmptyCell(position) => {
+                write!(f, "The robot at {position:?} is inside a wall or box")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InvariantError {}
+
+// each cell of a box relative to its top left corner, in order
+fn box_shape(box_cells: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let top = box_cells.iter().map(|&(i, _)| i).min().unwrap_or(0);
+    let left = box_cells.iter().map(|&(_, j)| j).min().unwrap_or(0);
+    let mut shape = box_cells
+        .iter()
+        .map(|&(i, j)| (i - top, j - left))
+        .collect::<Vec<_>>();
+    shape.sort();
+    shape
+}
+
+// a warehouse of boxes of any shape, each made of one or more cells that
+// always move together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoxGrid {
+    cells: Array2D<Cell>,
+    // the cells each box covers, indexed by its id
+    boxes: Vec<Vec<(usize, usize)>>,
+}
+
+impl BoxGrid {
+    pub fn new(
+        num_rows: usize,
+        num_columns: usize,
+        walls: impl IntoIterator<Item = (usize, usize)>,
+        boxes: Vec<Vec<(usize, usize)>>,
+    ) -> BoxGrid {
+        let mut cells = Array2D::filled_with(Cell::Empty, num_rows, num_columns);
+        for wall in walls {
+            cells[wall] = Cell::Wall;
+        }
+        for (id, box_cells) in boxes.iter().enumerate() {
+            for &position in box_cells {
+                cells[position] = Cell::Box(id);
+            }
+        }
+
+        BoxGrid { cells, boxes }
+    }
+
+    // every cell is stretched sideways into `scale` cells, so each box becomes
+    // `scale` wide. Part 1 is a scale of 1 and part 2 a scale of 2.
+    pub fn scaled(part1_grid: &Array2D<Part1Cell>, scale: usize) -> BoxGrid {
+        let scaled_columns = |j: usize| j * scale..(j + 1) * scale;
+
+        let walls = part1_grid
+            .enumerate_row_major()
+            .filter(|(_, cell)| matches!(cell, Part1Cell::Wall))
+            .flat_map(|((i, j), _)| scaled_columns(j).map(move |column| (i, column)));
+
+        let boxes = part1_grid
+            .enumerate_row_major()
+            .filter(|(_, cell)| matches!(cell, Part1Cell::Box))
+            .map(|((i, j), _)| scaled_columns(j).map(|column| (i, column)).collect())
+            .collect();
+
+        BoxGrid::new(
+            part1_grid.num_rows(),
+            part1_grid.num_columns() * scale,
+            walls,
+            boxes,
+        )
+    }
+
+    pub fn cells(&self) -> &Array2D<Cell> {
+        &self.cells
+    }
+
+    pub fn boxes(&self) -> &Vec<Vec<(usize, usize)>> {
+        &self.boxes
+    }
+
+    // the top row and left column of each box, which is what its gps
+    // coordinate is measured from
+    pub fn box_corners(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.boxes.iter().map(|box_cells| {
+            (
+                box_cells.iter().map(|&(i, _)| i).min().unwrap(),
+                box_cells.iter().map(|&(_, j)| j).min().unwrap(),
+            )
+        })
+    }
+
+    // the ids of every box that moves when the given box is pushed, or None
+    // if any of them is stuck against a wall
+    fn boxes_to_push(&self, first_box: usize, direction: Direction) -> Option<Vec<usize>> {
+        let mut to_push = vec![first_box];
+        let mut seen = HashSet::from([first_box]);
+        let mut i = 0;
+
+        while let Some(&id) = to_push.get(i) {
+            for &position in self.boxes[id].iter() {
+                match self.cells[move_once_in_direction(position, direction)] {
+                    Cell::Empty => (),
+                    Cell::Wall => return None,
+                    Cell::Box(other_id) => {
+                        if seen.insert(other_id) {
+                            to_push.push(other_id);
+                        }
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        Some(to_push)
+    }
+
+    // moves the boxes one step, lifting every box before putting any down
+    // so they can't overwrite each other
+    fn move_boxes(&mut self, ids: &[usize], direction: Direction) {
+        for &id in ids {
+            for &position in self.boxes[id].iter() {
+                self.cells[position] = Cell::Empty;
+            }
+        }
+        for &id in ids {
+            for position in self.boxes[id].iter_mut() {
+                *position = move_once_in_direction(*position, direction);
+                self.cells[*position] = Cell::Box(id);
+            }
+        }
+    }
+
+    // moves the robot one step, pushing any boxes in the way, as long as none
+    // of them are stuck. Returns the ids of the pushed boxes if the robot moved.
+    pub fn move_and_push(
+        &mut self,
+        robot_position: &mut (usize, usize),
+        direction: Direction,
+    ) -> Option<Vec<usize>> {
+        let next_robot_position = move_once_in_direction(*robot_position, direction);
+        let pushed = match self.cells[next_robot_position] {
+            Cell::Empty => Vec::new(),
+            Cell::Wall => return None,
+            Cell::Box(id) => {
+                let to_push = self.boxes_to_push(id, direction)?;
+                self.move_boxes(&to_push, direction);
+                to_push
+            }
+        };
+
+        *robot_position = next_robot_position;
+        Some(pushed)
+    }
+
+    // takes back a move_and_push that moved the robot in the given direction
+    pub fn undo_move_and_push(
+        &mut self,
+        robot_position: &mut (usize, usize),
+        direction: Direction,
+        pushed: &[usize],
+    ) {
+        self.move_boxes(pushed, direction.opposite());
+        *robot_position = move_once_in_direction(*robot_position, direction.opposite());
+    }
+
+    // checks that the boxes and walls still agree with how the grid started
+    // out, and that the robot isn't inside anything
+    pub fn check_invariants(
+        &self,
+        initial: &BoxGrid,
+        robot_position: (usize, usize),
+    ) -> Result<(), InvariantError> {
+        if self.boxes.len() != initial.boxes.len() {
+            return Err(InvariantError::BoxCountChanged(
+                initial.boxes.len(),
+                self.boxes.len(),
+            ));
+        }
+
+        for (id, (box_cells, initial_box_cells)) in
+            self.boxes.iter().zip(initial.boxes.iter()).enumerate()
+        {
+            if box_shape(box_cells) != box_shape(initial_box_cells) {
+                return Err(InvariantError::BoxShapeChanged(id));
+            }
+            if let Some(&position) = box_cells
+                .iter()
+                .find(|&&position| self.cells[position] != Cell::Box(id))
+            {
+                return Err(InvariantError::BoxCellNotMarked(id, position));
+            }
+        }
+
+        for (position, &cell) in self.cells.enumerate_row_major() {
+            match cell {
+                Cell::Box(id) => {
+                    if !self
+                        .boxes
+                        .get(id)
+                        .is_some_and(|cells| cells.contains(&position))
+                    {
+                        return Err(InvariantError::StrayBoxCell(id, position));
+                    }
+                }
+                Cell::Wall | Cell::Empty => (),
+            }
+            if (cell == Cell::Wall) != (initial.cells[position] == Cell::Wall) {
+                return Err(InvariantError::WallChanged(position));
+            }
+        }
+
+        if self.cells[robot_position] != Cell::Empty {
+            return Err(InvariantError::RobotNotOnEmptyCell(robot_position));
+        }
+
+        Ok(())
+    }
+}
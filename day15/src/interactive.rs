@@ -0,0 +1,132 @@
+use std::{
+    error::Error,
+    fs,
+    io::{stdout, Write},
+    path::Path,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    terminal::{self, ClearType},
+};
+
+use crate::{
+    box_grid::InvariantError, direction::Direction, gps, puzzle_input::PuzzleInput,
+    scaled_warehouse, warehouse::Warehouse,
+};
+
+const HELP: &str = "arrows or ^>v< move, u undo, p switch part, s save directions, q quit";
+
+// the warehouse at the given scale after replaying the keys pressed so far,
+// through the same pushes the solver makes
+pub fn warehouse_after(
+    input: &PuzzleInput,
+    scale: usize,
+    key_log: &[Direction],
+) -> Result<Warehouse, InvariantError> {
+    let mut warehouse = scaled_warehouse(
+        PuzzleInput {
+            directions: Vec::new(),
+            ..input.clone()
+        },
+        scale,
+    );
+    for &direction in key_log {
+        warehouse.push(direction)?;
+    }
+    Ok(warehouse)
+}
+
+fn draw(
+    warehouse: &Warehouse,
+    scale: usize,
+    num_moves: usize,
+    message: &str,
+) -> std::io::Result<()> {
+    let mut out = stdout();
+    queue!(out, terminal::Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+
+    // raw mode doesn't go back to the start of the line by itself
+    for line in warehouse.render().lines() {
+        write!(out, "{line}\r\n")?;
+    }
+    write!(
+        out,
+        "\r\nPart {scale}, {num_moves} moves, GPS sum {}\r\n{HELP}\r\n{message}\r\n",
+        warehouse.grid().box_corners().map(gps).sum::<usize>()
+    )?;
+
+    out.flush()
+}
+
+fn event_loop(input: &PuzzleInput, key_log_path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut scale = 1;
+    let mut key_log = Vec::new();
+    let mut warehouse = warehouse_after(input, scale, &key_log)?;
+    let mut message = String::new();
+
+    loop {
+        draw(&warehouse, scale, key_log.len(), &message)?;
+        message.clear();
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        let direction = match key.code {
+            KeyCode::Up => Some(Direction::North),
+            KeyCode::Right => Some(Direction::East),
+            KeyCode::Down => Some(Direction::South),
+            KeyCode::Left => Some(Direction::West),
+            KeyCode::Char(c) => Direction::try_from(c).ok(),
+            _ => None,
+        };
+
+        if let Some(direction) = direction {
+            warehouse.push(direction)?;
+            key_log.push(direction);
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('u') => {
+                warehouse.undo();
+                key_log.truncate(warehouse.num_steps());
+            }
+            KeyCode::Char('p') => {
+                scale = if scale == 1 { 2 } else { 1 };
+                warehouse = warehouse_after(input, scale, &key_log)?;
+            }
+            KeyCode::Char('s') => {
+                let directions = key_log.iter().copied().map(char::from).collect::<String>();
+                fs::write(key_log_path, directions + "\n")?;
+                message = format!(
+                    "Saved {} moves to {}",
+                    key_log.len(),
+                    key_log_path.display()
+                );
+            }
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            _ => (),
+        }
+    }
+}
+
+// drives the robot around the input's warehouse from the keyboard, starting
+// from the original layout with none of the puzzle's directions followed
+pub fn play(input: &PuzzleInput, key_log_path: &Path) -> Result<(), Box<dyn Error>> {
+    terminal::enable_raw_mode()?;
+    execute!(stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let result = event_loop(input, key_log_path);
+
+    execute!(stdout(), cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
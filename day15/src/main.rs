@@ -1,244 +1,150 @@
+mod box_grid;
 mod direction;
+mod interactive;
 mod part1_cell;
-mod part2_cell;
 mod puzzle_input;
+mod warehouse;
 
-use std::collections::BTreeSet;
+use std::{
+    fmt::{Display, Formatter},
+    path::Path,
+};
 
-use array2d::Array2D;
-use itertools::Itertools;
-
-use direction::Direction;
-use part1_cell::Part1Cell;
-use part2_cell::{to_part2_grid, Part2Cell};
+use box_grid::{BoxGrid, InvariantError};
 use puzzle_input::PuzzleInput;
+use warehouse::Warehouse;
 
 fn gps((i, j): (usize, usize)) -> usize {
     100 * i + j
 }
 
-fn move_once_in_direction((i, j): (usize, usize), direction: Direction) -> (usize, usize) {
-    // assume no overflow or underflow since walls are around the whole thang
-    match direction {
-        Direction::North => (i - 1, j),
-        Direction::East => (i, j + 1),
-        Direction::South => (i + 1, j),
-        Direction::West => (i, j - 1),
-    }
-}
+fn scaled_warehouse(input: PuzzleInput, scale: usize) -> Warehouse {
+    let PuzzleInput {
+        grid: part1_grid,
+        start_position,
+        directions,
+    } = input;
 
-fn move_and_push_part1(
-    grid: &mut Array2D<Part1Cell>,
-    robot_position: &mut (usize, usize),
-    direction: Direction,
-) {
-    let next_robot_position = move_once_in_direction(*robot_position, direction);
-    match grid[next_robot_position] {
-        Part1Cell::Empty => *robot_position = next_robot_position,
-        Part1Cell::Wall => (),
-        Part1Cell::Box => {
-            let mut next_box_position = move_once_in_direction(next_robot_position, direction);
-            loop {
-                match grid[next_box_position] {
-                    Part1Cell::Empty => {
-                        grid[next_box_position] = Part1Cell::Box;
-                        grid[next_robot_position] = Part1Cell::Empty;
-                        *robot_position = next_robot_position;
-                        return;
-                    }
-                    Part1Cell::Wall => return,
-                    Part1Cell::Box => {
-                        next_box_position = move_once_in_direction(next_box_position, direction)
-                    }
-                }
-            }
-        }
-    }
+    Warehouse::new(
+        BoxGrid::scaled(&part1_grid, scale),
+        (start_position.0, start_position.1 * scale),
+        directions,
+    )
 }
 
-fn try_push_part2_north_south(
-    grid: &mut Array2D<Part2Cell>,
-    box_position: (usize, usize),
-    direction: Direction,
-) -> bool {
-    let mut seen = BTreeSet::new();
+fn run(input: PuzzleInput, scale: usize) -> Result<usize, InvariantError> {
+    let mut warehouse = scaled_warehouse(input, scale);
+    warehouse.run()?;
+    Ok(warehouse.grid().box_corners().map(gps).sum())
+}
 
-    if !can_push_part2_north_south(grid, box_position, direction, &mut seen) {
-        return false;
-    }
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    Invariant(InvariantError),
+    // the number of steps taken when the replay stopped
+    Unfinished(usize),
+    UndoMismatch,
+}
 
-    if matches!(direction, Direction::North) {
-        for position in seen.into_iter() {
-            let to_move_to = move_once_in_direction(position, direction);
-            grid[to_move_to] = grid[position];
-            grid[position] = Part2Cell::Empty;
-        }
-    } else {
-        for position in seen.into_iter().rev() {
-            let to_move_to = move_once_in_direction(position, direction);
-            grid[to_move_to] = grid[position];
-            grid[position] = Part2Cell::Empty;
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use ReplayError::*;
+        match self {
+            Invariant(error) => write!(f, "{error}"),
+            Unfinished(num_steps) => write!(
+                f,
+                "The replay stopped after {num_steps} steps with directions left to follow"
+            ),
+            UndoMismatch => write!(f, "Undoing every step didn't get back to the initial state"),
         }
     }
-
-    true
 }
 
-fn can_push_part2_north_south(
-    grid: &mut Array2D<Part2Cell>,
-    box_position: (usize, usize),
-    direction: Direction,
-    seen: &mut BTreeSet<(usize, usize)>,
-) -> bool {
-    if seen.contains(&box_position) {
-        return true;
-    }
-
-    let direction_to_other_box_half = match grid[box_position] {
-        Part2Cell::LeftBox => Direction::East,
-        Part2Cell::RightBox => Direction::West,
-        _ => panic!(),
-    };
+impl std::error::Error for ReplayError {}
 
-    let other_box_position = move_once_in_direction(box_position, direction_to_other_box_half);
-
-    let next_box_position = move_once_in_direction(box_position, direction);
-
-    match grid[next_box_position] {
-        Part2Cell::Empty => (),
-        Part2Cell::Wall => return false,
-        Part2Cell::LeftBox | Part2Cell::RightBox => {
-            if !can_push_part2_north_south(grid, next_box_position, direction, seen) {
-                return false;
-            }
-        }
-    };
-
-    let next_other_box_position = move_once_in_direction(other_box_position, direction);
-
-    let result = match grid[next_other_box_position] {
-        Part2Cell::Empty => true,
-        Part2Cell::Wall => false,
-        Part2Cell::LeftBox | Part2Cell::RightBox => {
-            can_push_part2_north_south(grid, next_other_box_position, direction, seen)
-        }
-    };
-
-    if result {
-        seen.insert(box_position);
-        seen.insert(other_box_position);
+impl From<InvariantError> for ReplayError {
+    fn from(error: InvariantError) -> Self {
+        ReplayError::Invariant(error)
     }
-
-    result
 }
 
-fn try_push_part2_east_west(
-    grid: &mut Array2D<Part2Cell>,
-    box_position: (usize, usize),
-    direction: Direction,
-) -> bool {
-    let mut next_box_position = move_once_in_direction(box_position, direction);
-    loop {
-        match grid[next_box_position] {
-            Part2Cell::Empty => break,
-            Part2Cell::Wall => return false,
-            Part2Cell::LeftBox | Part2Cell::RightBox => {
-                next_box_position = move_once_in_direction(next_box_position, direction)
-            }
-        }
-    }
+// prints every frame like the puzzle's worked example, then takes every step
+// back to check it ends up where it started
+fn print_replay(mut warehouse: Warehouse) -> Result<(), ReplayError> {
+    let initial_state = warehouse.snapshot();
 
-    if next_box_position.1 > box_position.1 {
-        for (cur_col, prev_col) in (box_position.1..=next_box_position.1).rev().tuple_windows() {
-            grid[(box_position.0, cur_col)] = grid[(box_position.0, prev_col)];
-        }
-    } else {
-        for (cur_col, prev_col) in (next_box_position.1..=box_position.1).tuple_windows() {
-            grid[(box_position.0, cur_col)] = grid[(box_position.0, prev_col)];
-        }
-    };
+    println!("Initial state:\n{}\n", warehouse.render());
+    while let Some(direction) = warehouse.step()? {
+        println!("Move {}:\n{}\n", char::from(direction), warehouse.render());
+    }
+    let num_steps = warehouse.num_steps();
+    if !warehouse.is_finished() {
+        return Err(ReplayError::Unfinished(num_steps));
+    }
 
-    grid[box_position] = Part2Cell::Empty;
+    while warehouse.undo() {}
+    if initial_state != warehouse.snapshot() {
+        return Err(ReplayError::UndoMismatch);
+    }
+    println!("Undid all {num_steps} steps back to the initial state");
 
-    true
+    Ok(())
 }
 
-fn try_push_part2(
-    grid: &mut Array2D<Part2Cell>,
-    box_position: (usize, usize),
-    direction: Direction,
-) -> bool {
-    match direction {
-        Direction::North | Direction::South => {
-            try_push_part2_north_south(grid, box_position, direction)
-        }
-        Direction::East | Direction::West => {
-            try_push_part2_east_west(grid, box_position, direction)
-        }
-    }
-}
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidScale(String);
 
-fn move_and_push_part2(
-    grid: &mut Array2D<Part2Cell>,
-    robot_position: &mut (usize, usize),
-    direction: Direction,
-) {
-    let next_robot_position = move_once_in_direction(*robot_position, direction);
-    match grid[next_robot_position] {
-        Part2Cell::Empty => *robot_position = next_robot_position,
-        Part2Cell::Wall => (),
-        Part2Cell::LeftBox | Part2Cell::RightBox => {
-            if try_push_part2(grid, next_robot_position, direction) {
-                *robot_position = next_robot_position;
-            }
-        }
+impl Display for InvalidScale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Expected a whole number scale of at least 1 for '--replay', got '{}'",
+            self.0
+        )
     }
 }
 
-fn part1(input: PuzzleInput) -> usize {
-    let PuzzleInput {
-        mut grid,
-        start_position: mut cur_position,
-        directions,
-    } = input;
+impl std::error::Error for InvalidScale {}
 
-    for direction in directions {
-        move_and_push_part1(&mut grid, &mut cur_position, direction);
+// a warehouse scaled down to nothing has no room for the robot
+fn parse_scale(value: &str) -> Result<usize, InvalidScale> {
+    match value.parse() {
+        Ok(scale) if scale > 0 => Ok(scale),
+        _ => Err(InvalidScale(value.to_string())),
     }
-
-    grid.enumerate_row_major()
-        .filter(|&(_, &cell)| matches!(cell, Part1Cell::Box))
-        .map(|(position, _)| gps(position))
-        .sum()
 }
 
-fn part2(input: PuzzleInput) -> usize {
-    let PuzzleInput {
-        grid: part1_grid,
-        start_position,
-        directions,
-    } = input;
-
-    let mut grid = to_part2_grid(&part1_grid);
-    let mut cur_position = (start_position.0, start_position.1 * 2);
-
-    for direction in directions {
-        move_and_push_part2(&mut grid, &mut cur_position, direction);
-    }
+fn part1(input: PuzzleInput) -> Result<usize, InvariantError> {
+    run(input, 1)
+}
 
-    grid.enumerate_row_major()
-        .filter(|&(_, &cell)| matches!(cell, Part2Cell::LeftBox))
-        .map(|(position, _)| gps(position))
-        .sum()
+fn part2(input: PuzzleInput) -> Result<usize, InvariantError> {
+    run(input, 2)
 }
 
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     let file_contents_as_str = include_str!("../input.txt");
 
-    let input = PuzzleInput::parse_input(file_contents_as_str);
+    let input = PuzzleInput::parse_input(file_contents_as_str)?;
+
+    println!("{}", part1(input.clone())?);
+    println!("{}", part2(input.clone())?);
 
-    println!("{}", part1(input.clone()));
-    println!("{}", part2(input));
+    // --replay N prints every step with the warehouse scaled up N times,
+    // so 1 for part 1 and 2 for part 2. --play FILE drives the robot from
+    // the keyboard and saves the directions to FILE.
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.as_slice() {
+        [flag, scale] if flag == "--replay" => {
+            print_replay(scaled_warehouse(input, parse_scale(scale)?))?;
+        }
+        [flag, key_log_path] if flag == "--play" => {
+            interactive::play(&input, Path::new(key_log_path))?;
+        }
+        _ => (),
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -246,6 +152,7 @@ mod tests {
     use test_case::test_case;
 
     use super::*;
+    use direction::Direction;
 
     const TEST_INPUT_SMALL_PART_1: &str = "########\n\
                                            #..O.O.#\n\
@@ -262,13 +169,169 @@ mod tests {
     #[test_case(TEST_INPUT_SMALL_PART_1 => 2028 ; "small example")]
     #[test_case(TEST_INPUT_BIG => 10092 ; "big example")]
     fn test_part1(input: &str) -> usize {
-        let puzzle_input = PuzzleInput::parse_input(input);
-        part1(puzzle_input)
+        let puzzle_input = PuzzleInput::parse_input(input).unwrap();
+        part1(puzzle_input).unwrap()
     }
 
     #[test_case(TEST_INPUT_BIG => 9021 ; "big example")]
     fn test_part2(input: &str) -> usize {
-        let puzzle_input = PuzzleInput::parse_input(input);
-        part2(puzzle_input)
+        let puzzle_input = PuzzleInput::parse_input(input).unwrap();
+        part2(puzzle_input).unwrap()
+    }
+
+    #[test]
+    fn test_push_polyominoes() {
+        // a 6x6 room with an L shaped box, and a small box under its foot
+        let walls = (0..6).flat_map(|i| [(i, 0), (i, 5), (0, i), (5, i)]);
+        let l_box = vec![(2, 2), (3, 2), (3, 3)];
+        let mut grid = BoxGrid::new(6, 6, walls, vec![l_box, vec![(3, 4)]]);
+        let mut robot_position = (2, 1);
+
+        // pushing the upright of the L drags its foot along, which pushes the
+        // small box into the wall
+        assert!(grid
+            .move_and_push(&mut robot_position, Direction::East)
+            .is_none());
+        assert_eq!((2, 1), robot_position);
+
+        robot_position = (1, 2);
+        assert_eq!(
+            Some(vec![0]),
+            grid.move_and_push(&mut robot_position, Direction::South)
+        );
+        assert_eq!((2, 2), robot_position);
+        assert_eq!(vec![(3, 2), (3, 4)], grid.box_corners().collect::<Vec<_>>());
+
+        assert!(grid
+            .move_and_push(&mut robot_position, Direction::South)
+            .is_none());
+        assert_eq!((2, 2), robot_position);
+    }
+
+    #[test]
+    fn test_replay_part2() {
+        let input = PuzzleInput::parse_input(
+            "#######\n\
+             #...#.#\n\
+             #.....#\n\
+             #..OO@#\n\
+             #..O..#\n\
+             #.....#\n\
+             #######\n\n\
+             <vv<<^^<<^^",
+        )
+        .unwrap();
+        let mut warehouse = scaled_warehouse(input, 2);
+        let initial_state = warehouse.snapshot();
+
+        assert_eq!(
+            "##############\n\
+             ##......##..##\n\
+             ##..........##\n\
+             ##....[][]@.##\n\
+             ##....[]....##\n\
+             ##..........##\n\
+             ##############",
+            warehouse.render()
+        );
+
+        assert_eq!(Ok(Some(Direction::West)), warehouse.step());
+        assert_eq!(
+            "##############\n\
+             ##......##..##\n\
+             ##..........##\n\
+             ##...[][]@..##\n\
+             ##....[]....##\n\
+             ##..........##\n\
+             ##############",
+            warehouse.render()
+        );
+
+        warehouse.run().unwrap();
+        assert_eq!(11, warehouse.num_steps());
+        assert_eq!(
+            "##############\n\
+             ##...[].##..##\n\
+             ##...@.[]...##\n\
+             ##....[]....##\n\
+             ##..........##\n\
+             ##..........##\n\
+             ##############",
+            warehouse.render()
+        );
+
+        while warehouse.undo() {}
+        assert_eq!(initial_state, warehouse.snapshot());
+    }
+
+    #[test_case("" => puzzle_input::Error::EmptyGrid ; "empty")]
+    #[test_case("###\n#@x\n###\n\n<" => puzzle_input::Error::UnrecognizedCharacter(2, 3, 'x') ; "unrecognized character")]
+    #[test_case("###\n#@#\n##\n\n<" => puzzle_input::Error::RaggedRow(3) ; "ragged row")]
+    #[test_case("###\n#.#\n###\n\n<" => puzzle_input::Error::NoRobot ; "no robot")]
+    #[test_case("####\n#@@#\n####\n\n<" => puzzle_input::Error::MultipleRobots(2, 3) ; "two robots")]
+    #[test_case("###\n#@.\n###\n\n<" => puzzle_input::Error::NotEnclosed(2, 3) ; "gap in the wall")]
+    #[test_case("###\n#@#\n###\n\n<>\n^x" => puzzle_input::Error::UnrecognizedDirection(6, 2, 'x') ; "unrecognized direction")]
+    fn test_parse_errors(input: &str) -> puzzle_input::Error {
+        PuzzleInput::parse_input(input).unwrap_err()
+    }
+
+    #[test_case("1" => Ok(1) ; "part 1")]
+    #[test_case("2" => Ok(2) ; "part 2")]
+    #[test_case("0" => Err(InvalidScale("0".to_string())) ; "zero")]
+    #[test_case("-1" => Err(InvalidScale("-1".to_string())) ; "negative")]
+    #[test_case("two" => Err(InvalidScale("two".to_string())) ; "not a number")]
+    fn test_parse_scale(value: &str) -> Result<usize, InvalidScale> {
+        parse_scale(value)
+    }
+
+    #[test]
+    fn test_invariants() {
+        let walls = || (0..4).flat_map(|i| [(i, 0), (i, 4), (0, i), (3, i)]);
+        let wide_box = vec![(1, 1), (1, 2)];
+        let initial = BoxGrid::new(4, 5, walls(), vec![wide_box.clone()]);
+        assert_eq!(Ok(()), initial.check_invariants(&initial, (2, 1)));
+
+        assert_eq!(
+            Err(InvariantError::RobotNotOnEmptyCell((1, 2))),
+            initial.check_invariants(&initial, (1, 2))
+        );
+
+        let half_box = BoxGrid::new(4, 5, walls(), vec![vec![(1, 1)]]);
+        assert_eq!(
+            Err(InvariantError::BoxShapeChanged(0)),
+            half_box.check_invariants(&initial, (2, 1))
+        );
+
+        // the second box covers up part of the first
+        let overlapping = BoxGrid::new(4, 5, walls(), vec![wide_box.clone(), vec![(1, 2)]]);
+        let initial_pair = BoxGrid::new(4, 5, walls(), vec![wide_box.clone(), vec![(2, 3)]]);
+        assert_eq!(
+            Err(InvariantError::BoxCellNotMarked(0, (1, 2))),
+            overlapping.check_invariants(&initial_pair, (2, 1))
+        );
+        assert_eq!(
+            Err(InvariantError::BoxCountChanged(2, 1)),
+            initial.check_invariants(&initial_pair, (2, 1))
+        );
+
+        let extra_wall = BoxGrid::new(4, 5, walls().chain([(2, 2)]), vec![wide_box]);
+        assert_eq!(
+            Err(InvariantError::WallChanged((2, 2))),
+            extra_wall.check_invariants(&initial, (2, 1))
+        );
+    }
+
+    #[test]
+    fn test_interactive_replays_like_the_solver() {
+        let input = PuzzleInput::parse_input(TEST_INPUT_BIG).unwrap();
+
+        for scale in [1, 2] {
+            let mut solver_warehouse = scaled_warehouse(input.clone(), scale);
+            solver_warehouse.run().unwrap();
+
+            let played_warehouse =
+                interactive::warehouse_after(&input, scale, &input.directions).unwrap();
+            assert_eq!(solver_warehouse.snapshot(), played_warehouse.snapshot());
+        }
     }
 }
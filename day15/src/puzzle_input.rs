@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 use array2d::Array2D;
 
 use crate::{direction::Direction, part1_cell::Part1Cell};
@@ -9,46 +11,123 @@ pub struct PuzzleInput {
     pub directions: Vec<Direction>,
 }
 
+// line and column numbers start at 1, like in an editor
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    EmptyGrid,
+    UnrecognizedCharacter(usize, usize, char),
+    RaggedRow(usize),
+    NoRobot,
+    MultipleRobots(usize, usize),
+    NotEnclosed(usize, usize),
+    UnrecognizedDirection(usize, usize, char),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Error::*;
+        match self {
+            EmptyGrid => write!(f, "The input doesn't start with a map of the warehouse"),
+            UnrecognizedCharacter(line, column, c) => write!(
+                f,
+                "Line {line}, column {column}: '{c}' isn't one of #, O, . or @"
+            ),
+            RaggedRow(line) => write!(
+                f,
+                "Line {line}: the row is a different length to the first row"
+            ),
+            NoRobot => write!(f, "The map has no robot (@)"),
+            MultipleRobots(line, column) => {
+                write!(f, "Line {line}, column {column}: found a second robot")
+            }
+            NotEnclosed(line, column) => write!(
+                f,
+                "Line {line}, column {column}: the edge of the map needs to be a wall"
+            ),
+            UnrecognizedDirection(line, column, c) => write!(
+                f,
+                "Line {line}, column {column}: '{c}' isn't one of ^, >, v or <"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 impl PuzzleInput {
-    pub fn parse_input(input_str: &str) -> PuzzleInput {
-        let mut lines = input_str.lines();
+    // the map, a blank line, then the directions over any number of lines.
+    // The map has to have exactly one robot and walls all the way round.
+    pub fn parse_input(input_str: &str) -> Result<PuzzleInput, Error> {
+        let mut lines = input_str.lines().map(str::trim_end).enumerate();
 
         let input_grid_vecs = (&mut lines)
-            .take_while(|line| !line.is_empty())
-            .map(|line| {
+            .take_while(|(_, line)| !line.is_empty())
+            .map(|(i, line)| {
                 line.chars()
-                    .map(|c| InputCell::try_from(c).unwrap())
-                    .collect::<Vec<_>>()
+                    .enumerate()
+                    .map(|(j, c)| {
+                        InputCell::try_from(c)
+                            .map_err(|c| Error::UnrecognizedCharacter(i + 1, j + 1, c))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let start_position = input_grid_vecs
+        let num_rows = input_grid_vecs.len();
+        let num_columns = input_grid_vecs.first().ok_or(Error::EmptyGrid)?.len();
+        if let Some(i) = input_grid_vecs
+            .iter()
+            .position(|row| row.len() != num_columns)
+        {
+            return Err(Error::RaggedRow(i + 1));
+        }
+
+        let mut robot_positions = input_grid_vecs
             .iter()
             .enumerate()
             .flat_map(|(i, row)| row.iter().enumerate().map(move |(j, cell)| ((i, j), cell)))
-            .find(|&(_, &cell)| matches!(cell, InputCell::Robot))
-            .unwrap()
-            .0;
+            .filter(|&(_, &cell)| matches!(cell, InputCell::Robot))
+            .map(|(position, _)| position);
+        let start_position = robot_positions.next().ok_or(Error::NoRobot)?;
+        if let Some((i, j)) = robot_positions.next() {
+            return Err(Error::MultipleRobots(i + 1, j + 1));
+        }
+
+        let is_edge =
+            |(i, j): (usize, usize)| i == 0 || j == 0 || i + 1 == num_rows || j + 1 == num_columns;
+        if let Some((i, j)) = (0..num_rows)
+            .flat_map(|i| (0..num_columns).map(move |j| (i, j)))
+            .find(|&(i, j)| {
+                is_edge((i, j))
+                    && !matches!(input_grid_vecs[i][j], InputCell::GridCell(Part1Cell::Wall))
+            })
+        {
+            return Err(Error::NotEnclosed(i + 1, j + 1));
+        }
 
         let grid = Array2D::from_iter_row_major(
             input_grid_vecs
                 .iter()
                 .flat_map(|row| row.iter().copied().map(Part1Cell::from)),
-            input_grid_vecs.len(),
-            input_grid_vecs[0].len(),
+            num_rows,
+            num_columns,
         )
         .unwrap();
 
         let directions = lines
-            .flat_map(str::chars)
-            .map(|c| Direction::try_from(c).unwrap())
-            .collect();
+            .flat_map(|(i, line)| {
+                line.chars().enumerate().map(move |(j, c)| {
+                    Direction::try_from(c)
+                        .map_err(|c| Error::UnrecognizedDirection(i + 1, j + 1, c))
+                })
+            })
+            .collect::<Result<_, _>>()?;
 
-        PuzzleInput {
+        Ok(PuzzleInput {
             grid,
             start_position,
             directions,
-        }
+        })
     }
 }
 
@@ -0,0 +1,137 @@
+use crate::{
+    box_grid::{BoxGrid, Cell, InvariantError},
+    direction::Direction,
+};
+
+// everything needed to put the warehouse back the way it was
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub grid: BoxGrid,
+    pub robot_position: (usize, usize),
+    pub num_steps: usize,
+}
+
+// a move the robot made and the boxes it pushed, None if it hit something
+type Step = (Direction, Option<Vec<usize>>);
+
+// steps the robot through its directions one at a time, remembering each
+// step so it can be taken back
+pub struct Warehouse {
+    initial_grid: BoxGrid,
+    grid: BoxGrid,
+    robot_position: (usize, usize),
+    directions: Vec<Direction>,
+    history: Vec<Step>,
+}
+
+impl Warehouse {
+    pub fn new(grid: BoxGrid, robot_position: (usize, usize), directions: Vec<Direction>) -> Self {
+        Warehouse {
+            initial_grid: grid.clone(),
+            grid,
+            robot_position,
+            directions,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn grid(&self) -> &BoxGrid {
+        &self.grid
+    }
+
+    pub fn num_steps(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.history.len() >= self.directions.len()
+    }
+
+    pub fn check_invariants(&self) -> Result<(), InvariantError> {
+        self.grid
+            .check_invariants(&self.initial_grid, self.robot_position)
+    }
+
+    // moves the robot in the given direction, whether or not it's next in its
+    // directions. Returns whether the robot moved. Only debug builds, which
+    // the tests use, check the invariants after every move; release builds
+    // skip the check, so call check_invariants to run it there.
+    pub fn push(&mut self, direction: Direction) -> Result<bool, InvariantError> {
+        let pushed = self.grid.move_and_push(&mut self.robot_position, direction);
+        let moved = pushed.is_some();
+        self.history.push((direction, pushed));
+
+        if cfg!(debug_assertions) {
+            self.check_invariants()?;
+        }
+
+        Ok(moved)
+    }
+
+    // follows the next of the robot's directions, None once they've run out
+    pub fn step(&mut self) -> Result<Option<Direction>, InvariantError> {
+        let Some(&direction) = self.directions.get(self.history.len()) else {
+            return Ok(None);
+        };
+        self.push(direction)?;
+        Ok(Some(direction))
+    }
+
+    pub fn run(&mut self) -> Result<(), InvariantError> {
+        while self.step()?.is_some() {}
+        Ok(())
+    }
+
+    // takes back the last step, returning false if there's nothing to undo
+    pub fn undo(&mut self) -> bool {
+        let Some((direction, pushed)) = self.history.pop() else {
+            return false;
+        };
+        if let Some(pushed) = pushed {
+            self.grid
+                .undo_move_and_push(&mut self.robot_position, direction, &pushed);
+        }
+        true
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            grid: self.grid.clone(),
+            robot_position: self.robot_position,
+            num_steps: self.num_steps(),
+        }
+    }
+
+    // the puzzle's notation: # for walls, @ for the robot, O for a small box
+    // and [] for a wide one. Boxes of other shapes are drawn as a letter.
+    pub fn render(&self) -> String {
+        let cells = self.grid.cells();
+        let boxes = self.grid.boxes();
+
+        let render_cell = |position: (usize, usize)| match cells[position] {
+            _ if position == self.robot_position => '@',
+            Cell::Empty => '.',
+            Cell::Wall => '#',
+            Cell::Box(id) => match boxes[id].as_slice() {
+                [_] => 'O',
+                [left, right] if left.0 == right.0 && left.1 + 1 == right.1 => {
+                    if position == *left {
+                        '['
+                    } else {
+                        ']'
+                    }
+                }
+                _ => (b'a' + (id % 26) as u8) as char,
+            },
+        };
+
+        (0..cells.num_rows())
+            .map(|i| {
+                (0..cells.num_columns())
+                    .map(|j| render_cell((i, j)))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -30,12 +30,14 @@ impl<S> PartialEq for PriorityQueueItem<S> {
 
 impl<S> Eq for PriorityQueueItem<S> {}
 
-pub fn best_cost_a_star<S, F, I, G, H>(
+// returns the cost of the best path along with every state on it,
+// from the start state it came from to the goal
+pub fn best_path_a_star<S, F, I, G, H>(
     start_states: impl IntoIterator<Item = (u64, S)>,
     successors: F,
     is_goal: G,
     heuristic: H,
-) -> Option<u64>
+) -> Option<(u64, Vec<S>)>
 where
     S: PartialEq + Eq + Hash + Clone,
     F: Fn(&S) -> I,
@@ -55,6 +57,7 @@ where
         );
     let mut seen: HashSet<S> = HashSet::new();
     let mut costs: HashMap<S, u64> = HashMap::new();
+    let mut predecessors: HashMap<S, S> = HashMap::new();
 
     while let Some(item) = frontier.pop() {
         if seen.contains(&item.state) {
@@ -62,7 +65,12 @@ where
         }
 
         if is_goal(&item.state) {
-            return Some(item.cost);
+            let mut path = vec![item.state];
+            while let Some(prev_state) = predecessors.get(path.last().unwrap()) {
+                path.push(prev_state.clone());
+            }
+            path.reverse();
+            return Some((item.cost, path));
         }
 
         seen.insert(item.state.clone());
@@ -85,6 +93,7 @@ where
                 costs.insert(successor.clone(), new_cost);
             }
 
+            predecessors.insert(successor.clone(), item.state.clone());
             frontier.push(PriorityQueueItem {
                 priority: new_cost + heuristic(&successor),
                 cost: new_cost,
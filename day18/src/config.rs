@@ -0,0 +1,112 @@
+use std::{
+    fmt::{Display, Formatter},
+    path::PathBuf,
+};
+
+pub struct Config {
+    // the puzzle input when not given
+    pub input: Option<PathBuf>,
+    // how far the first coordinate of a byte goes, across the rendered grid
+    pub width: usize,
+    // how far the second coordinate goes, down the rendered grid
+    pub height: usize,
+    pub num_bytes_to_fall: usize,
+    pub render: bool,
+    // also find the earliest arrival with bytes landing while walking
+    pub falling: bool,
+    // answer part 2 a byte at a time instead of all at once
+    pub online: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            input: None,
+            width: 71,
+            height: 71,
+            num_bytes_to_fall: 1024,
+            render: false,
+            falling: false,
+            online: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    MissingValue(String),
+    InvalidValue(String, String),
+    UnknownArgument(String),
+    EmptyGrid,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Error::*;
+        match self {
+            MissingValue(flag) => write!(f, "No value given for '{flag}'"),
+            InvalidValue(flag, value) => {
+                write!(
+                    f,
+                    "Expected a non-negative integer for '{flag}', got '{value}'"
+                )
+            }
+            UnknownArgument(arg) => write!(
+                f,
+                "Unknown argument '{arg}', expected --input FILE, --width N, --height N, \
+                 --bytes N, --render, --falling or --online"
+            ),
+            EmptyGrid => write!(f, "The grid needs a width and height of at least 1"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Config {
+    // --input FILE --width N --height N --bytes N --render --falling --online,
+    // anything left out keeps its default
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Config, Error> {
+        let mut config = Config::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let field = match arg.as_str() {
+                "--width" => &mut config.width,
+                "--height" => &mut config.height,
+                "--bytes" => &mut config.num_bytes_to_fall,
+                "--render" => {
+                    config.render = true;
+                    continue;
+                }
+                "--falling" => {
+                    config.falling = true;
+                    continue;
+                }
+                "--online" => {
+                    config.online = true;
+                    continue;
+                }
+                "--input" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| Error::MissingValue(arg.clone()))?;
+                    config.input = Some(PathBuf::from(path));
+                    continue;
+                }
+                _ => return Err(Error::UnknownArgument(arg)),
+            };
+
+            let value = args
+                .next()
+                .ok_or_else(|| Error::MissingValue(arg.clone()))?;
+            *field = value.parse().map_err(|_| Error::InvalidValue(arg, value))?;
+        }
+
+        if config.width == 0 || config.height == 0 {
+            return Err(Error::EmptyGrid);
+        }
+
+        Ok(config)
+    }
+}
@@ -0,0 +1,147 @@
+use array2d::Array2D;
+
+use crate::{grid_cell::GridCell, union_find::UnionFind};
+
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+fn neighbors(
+    (i, j): (usize, usize),
+    offsets: &[(isize, isize)],
+    num_rows: usize,
+    num_columns: usize,
+) -> impl '_ + Iterator<Item = (usize, usize)> {
+    offsets.iter().filter_map(move |&(di, dj)| {
+        let next_position = (i.checked_add_signed(di)?, j.checked_add_signed(dj)?);
+        (next_position.0 < num_rows && next_position.1 < num_columns).then_some(next_position)
+    })
+}
+
+// Offline: drop every byte, then take them back out in reverse order,
+// joining each freed cell to its safe neighbors. The first byte whose
+// removal connects the two corners is the first byte that blocked the exit.
+pub fn first_blocking_byte(
+    coordinates: &[(usize, usize)],
+    num_rows: usize,
+    num_columns: usize,
+) -> Option<(usize, usize)> {
+    let index = |(i, j): (usize, usize)| i * num_columns + j;
+    let start = index((0, 0));
+    let goal = index((num_rows - 1, num_columns - 1));
+
+    // a byte can land on the same cell more than once
+    let mut num_bytes_on_cell = Array2D::filled_with(0usize, num_rows, num_columns);
+    for &coord in coordinates {
+        num_bytes_on_cell[coord] += 1;
+    }
+
+    let mut union_find = UnionFind::new(num_rows * num_columns);
+
+    let free_cell = |position, union_find: &mut UnionFind, num_bytes_on_cell: &Array2D<usize>| {
+        for neighbor in neighbors(position, &ORTHOGONAL, num_rows, num_columns) {
+            if num_bytes_on_cell[neighbor] == 0 {
+                union_find.union(index(position), index(neighbor));
+            }
+        }
+    };
+
+    for (position, &num_bytes) in num_bytes_on_cell.enumerate_row_major() {
+        if num_bytes == 0 {
+            free_cell(position, &mut union_find, &num_bytes_on_cell);
+        }
+    }
+
+    if num_bytes_on_cell[(0, 0)] == 0
+        && num_bytes_on_cell[(num_rows - 1, num_columns - 1)] == 0
+        && union_find.connected(start, goal)
+    {
+        return None;
+    }
+
+    for &coord in coordinates.iter().rev() {
+        num_bytes_on_cell[coord] -= 1;
+        if num_bytes_on_cell[coord] > 0 {
+            continue;
+        }
+
+        free_cell(coord, &mut union_find, &num_bytes_on_cell);
+
+        if num_bytes_on_cell[(0, 0)] == 0
+            && num_bytes_on_cell[(num_rows - 1, num_columns - 1)] == 0
+            && union_find.connected(start, goal)
+        {
+            return Some(coord);
+        }
+    }
+
+    None
+}
+
+// Online: the exit is cut off exactly when the corrupted cells (touching
+// diagonally counts) form a wall from the top or right edge to the bottom
+// or left edge, so track which edges each clump of bytes touches.
+pub struct ByteConnectivity {
+    grid: Array2D<GridCell>,
+    union_find: UnionFind,
+}
+
+impl ByteConnectivity {
+    const ALL_DIRECTIONS: [(isize, isize); 8] = [
+        (-1, -1),
+        (-1, 0),
+        (-1, 1),
+        (0, -1),
+        (0, 1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+    ];
+
+    pub fn new(num_rows: usize, num_columns: usize) -> Self {
+        ByteConnectivity {
+            grid: Array2D::filled_with(GridCell::Safe, num_rows, num_columns),
+            // one element per cell, then the top/right edge and the bottom/left edge
+            union_find: UnionFind::new(num_rows * num_columns + 2),
+        }
+    }
+
+    fn index(&self, (i, j): (usize, usize)) -> usize {
+        i * self.grid.num_columns() + j
+    }
+
+    fn top_right_edge(&self) -> usize {
+        self.grid.num_elements()
+    }
+
+    fn bottom_left_edge(&self) -> usize {
+        self.grid.num_elements() + 1
+    }
+
+    // returns whether the exit is still reachable after the byte falls
+    pub fn add_byte(&mut self, position: (usize, usize)) -> bool {
+        let (num_rows, num_columns) = (self.grid.num_rows(), self.grid.num_columns());
+        self.grid[position] = GridCell::Corrupted;
+
+        let cell = self.index(position);
+
+        if position.0 == 0 || position.1 == num_columns - 1 {
+            self.union_find.union(cell, self.top_right_edge());
+        }
+        if position.0 == num_rows - 1 || position.1 == 0 {
+            self.union_find.union(cell, self.bottom_left_edge());
+        }
+
+        for neighbor in neighbors(position, &Self::ALL_DIRECTIONS, num_rows, num_columns) {
+            if self.grid[neighbor] == GridCell::Corrupted {
+                self.union_find.union(cell, self.index(neighbor));
+            }
+        }
+
+        self.is_exit_reachable()
+    }
+
+    pub fn is_exit_reachable(&mut self) -> bool {
+        let top_right_edge = self.top_right_edge();
+        let bottom_left_edge = self.bottom_left_edge();
+        !self.union_find.connected(top_right_edge, bottom_left_edge)
+    }
+}
@@ -0,0 +1,84 @@
+use array2d::Array2D;
+
+use crate::{a_star::best_path_a_star, grid_cell::GridCell};
+
+// byte i lands at time i, and the walker has to be off a cell before
+// the byte lands on it
+pub struct FallingBytes {
+    // time the first byte lands on each cell, None if no byte ever does
+    landing_times: Array2D<Option<u64>>,
+    // every byte has landed by this time, so the grid doesn't change after it
+    settled_time: u64,
+}
+
+impl FallingBytes {
+    pub fn new(
+        byte_coordinates: impl IntoIterator<Item = (usize, usize)>,
+        num_rows: usize,
+        num_columns: usize,
+    ) -> Self {
+        let mut landing_times = Array2D::filled_with(None, num_rows, num_columns);
+        let mut settled_time = 0;
+
+        for (time, coord) in (0..).zip(byte_coordinates) {
+            landing_times[coord].get_or_insert(time);
+            settled_time = time + 1;
+        }
+
+        FallingBytes {
+            landing_times,
+            settled_time,
+        }
+    }
+
+    pub fn cell_at(&self, position: (usize, usize), time: u64) -> GridCell {
+        match self.landing_times[position] {
+            Some(landing_time) if landing_time <= time => GridCell::Corrupted,
+            _ => GridCell::Safe,
+        }
+    }
+
+    // earliest time the walker can reach the bottom right corner, moving one
+    // cell or waiting in place each tick, along with its position at every tick
+    pub fn earliest_arrival(&self) -> Option<(u64, Vec<(usize, usize)>)> {
+        let num_rows = self.landing_times.num_rows();
+        let num_columns = self.landing_times.num_columns();
+        let goal = (num_rows - 1, num_columns - 1);
+
+        if self.cell_at((0, 0), 0) == GridCell::Corrupted {
+            return None;
+        }
+
+        // times past settled_time all look the same, so they share a state,
+        // which keeps the search finite when the exit is cut off
+        let start_states = [(0, ((0, 0), 0))];
+        let successors = |&((i, j), time): &((usize, usize), u64)| {
+            let next_time = (time + 1).min(self.settled_time);
+            [(0, 0), (-1, 0), (0, 1), (1, 0), (0, -1)]
+                .into_iter()
+                .filter_map(move |(di, dj): (isize, isize)| {
+                    let next_position = (i.checked_add_signed(di)?, j.checked_add_signed(dj)?);
+                    if next_position.0 < num_rows
+                        && next_position.1 < num_columns
+                        && self.cell_at(next_position, next_time) == GridCell::Safe
+                        && (next_position, next_time) != ((i, j), time)
+                    {
+                        Some((1, (next_position, next_time)))
+                    } else {
+                        None
+                    }
+                })
+        };
+        let is_goal = |&(position, _): &((usize, usize), u64)| position == goal;
+        let heuristic = |&(position, _): &((usize, usize), u64)| {
+            (position.0.abs_diff(goal.0) + position.1.abs_diff(goal.1)) as u64
+        };
+
+        let (arrival_time, path) = best_path_a_star(start_states, successors, is_goal, heuristic)?;
+
+        Some((
+            arrival_time,
+            path.into_iter().map(|(position, _)| position).collect(),
+        ))
+    }
+}
@@ -1,32 +1,39 @@
 mod a_star;
-mod binary_search;
+mod config;
+mod connectivity;
+mod falling_bytes;
 mod grid_cell;
+mod puzzle_input;
+mod union_find;
 
 use array2d::Array2D;
 
-use a_star::best_cost_a_star;
-use binary_search::BinarySearchExt;
+use a_star::best_path_a_star;
+use config::Config;
+use connectivity::{first_blocking_byte, ByteConnectivity};
+use falling_bytes::FallingBytes;
 use grid_cell::GridCell;
+use puzzle_input::parse_bytes;
 
-fn parse_coordinates(value: &str) -> (usize, usize) {
-    let (i_str, j_str) = value.split_once(',').unwrap();
-    (i_str.parse().unwrap(), j_str.parse().unwrap())
-}
-
-fn part1(
+fn corrupted_grid(
     byte_coordinates: impl IntoIterator<Item = (usize, usize)>,
     num_rows: usize,
     num_columns: usize,
     num_bytes_to_fall: usize,
-) -> Option<u64> {
+) -> Array2D<GridCell> {
     let mut grid = Array2D::filled_with(GridCell::Safe, num_rows, num_columns);
 
     for coord in byte_coordinates.into_iter().take(num_bytes_to_fall) {
         grid[coord] = GridCell::Corrupted;
     }
 
-    let grid = grid;
-    let grid_ref = &grid;
+    grid
+}
+
+// returns the number of steps along with every position on the path
+fn shortest_path(grid: &Array2D<GridCell>) -> Option<(u64, Vec<(usize, usize)>)> {
+    let num_rows = grid.num_rows();
+    let num_columns = grid.num_columns();
 
     let goal = (num_rows - 1, num_columns - 1);
 
@@ -37,7 +44,7 @@ fn part1(
                 let next_position = (i.checked_add_signed(di)?, j.checked_add_signed(dj)?);
                 if next_position.0 < num_rows
                     && next_position.1 < num_columns
-                    && grid_ref[next_position] == GridCell::Safe
+                    && grid[next_position] == GridCell::Safe
                 {
                     Some((1, next_position))
                 } else {
@@ -50,7 +57,40 @@ fn part1(
     let heuristic =
         |state: &(usize, usize)| (state.0.abs_diff(goal.0) + state.1.abs_diff(goal.1)) as u64;
 
-    best_cost_a_star(start_states, successors, is_goal, heuristic)
+    best_path_a_star(start_states, successors, is_goal, heuristic)
+}
+
+// draws the grid like the puzzle does, with the first coordinate of each byte
+// going across, '#' for corrupted memory and 'O' for the path
+fn render(grid: &Array2D<GridCell>, path: &[(usize, usize)]) -> String {
+    let mut chars = Array2D::filled_with('.', grid.num_columns(), grid.num_rows());
+
+    for ((x, y), cell) in grid.enumerate_row_major() {
+        if *cell == GridCell::Corrupted {
+            chars[(y, x)] = '#';
+        }
+    }
+
+    for &(x, y) in path {
+        chars[(y, x)] = 'O';
+    }
+
+    chars
+        .rows_iter()
+        .map(|row| row.collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn part1(
+    byte_coordinates: impl IntoIterator<Item = (usize, usize)>,
+    num_rows: usize,
+    num_columns: usize,
+    num_bytes_to_fall: usize,
+) -> Option<u64> {
+    let grid = corrupted_grid(byte_coordinates, num_rows, num_columns, num_bytes_to_fall);
+
+    shortest_path(&grid).map(|(cost, _)| cost)
 }
 
 fn part2(
@@ -58,36 +98,89 @@ fn part2(
     num_rows: usize,
     num_columns: usize,
 ) -> Option<(usize, usize)> {
-    let pp = (0..coordinates.len()).partition_point(|&i| {
-        part1(coordinates.iter().copied(), num_rows, num_columns, i + 1).is_some()
-    });
-    coordinates.get(pp).cloned()
+    first_blocking_byte(coordinates, num_rows, num_columns)
+}
+
+fn part2_online(
+    coordinates: &[(usize, usize)],
+    num_rows: usize,
+    num_columns: usize,
+) -> Option<(usize, usize)> {
+    let mut connectivity = ByteConnectivity::new(num_rows, num_columns);
+    coordinates
+        .iter()
+        .copied()
+        .find(|&coord| !connectivity.add_byte(coord))
 }
 
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     const INPUT: &str = include_str!("../input.txt");
-    const NUM_ROWS: usize = 71;
-    const NUM_COLUMNS: usize = 71;
-    const NUM_BYTES_TO_FALL_PART1: usize = 1024;
+
+    let Config {
+        input,
+        width,
+        height,
+        num_bytes_to_fall,
+        render: should_render,
+        falling,
+        online,
+    } = Config::from_args(std::env::args().skip(1))?;
+
+    // bytes are stored at grid[(x, y)], so the rows of the grid go across
+    let (num_rows, num_columns) = (width, height);
+
+    let input = match input {
+        Some(path) => std::fs::read_to_string(path)?,
+        None => INPUT.to_string(),
+    };
+    let coordinates = parse_bytes(&input, num_rows, num_columns)?;
 
     println!(
         "{:?}",
         part1(
-            INPUT.lines().map(parse_coordinates),
-            NUM_ROWS,
-            NUM_COLUMNS,
-            NUM_BYTES_TO_FALL_PART1,
+            coordinates.iter().copied(),
+            num_rows,
+            num_columns,
+            num_bytes_to_fall,
         )
     );
 
-    let coordinates = INPUT.lines().map(parse_coordinates).collect::<Vec<_>>();
+    if should_render {
+        let grid = corrupted_grid(
+            coordinates.iter().copied(),
+            num_rows,
+            num_columns,
+            num_bytes_to_fall,
+        );
+        let path = shortest_path(&grid)
+            .map(|(_, path)| path)
+            .unwrap_or_default();
+        println!("{}", render(&grid, &path));
+    }
+
+    if online {
+        println!("{:?}", part2_online(&coordinates, num_rows, num_columns));
+    } else {
+        println!("{:?}", part2(&coordinates, num_rows, num_columns));
+    }
 
-    println!("{:?}", part2(&coordinates, NUM_ROWS, NUM_COLUMNS));
+    if falling {
+        let falling_bytes = FallingBytes::new(coordinates.iter().copied(), num_rows, num_columns);
+        println!(
+            "{:?}",
+            falling_bytes
+                .earliest_arrival()
+                .map(|(arrival_time, _)| arrival_time)
+        );
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use puzzle_input::parse_coordinates;
 
     const TEST_INPUT: &str = include_str!("../example.txt");
     const TEST_NUM_ROWS: usize = 7;
@@ -96,7 +189,9 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let coordinates_iter = TEST_INPUT.lines().map(parse_coordinates);
+        let coordinates_iter = TEST_INPUT
+            .lines()
+            .map(|line| parse_coordinates(line).unwrap());
 
         assert_eq!(
             part1(
@@ -113,12 +208,189 @@ mod tests {
     fn test_part2() {
         let coordinates = TEST_INPUT
             .lines()
-            .map(parse_coordinates)
+            .map(|line| parse_coordinates(line).unwrap())
             .collect::<Vec<_>>();
 
         assert_eq!(
             Some((6, 1)),
             part2(&coordinates, TEST_NUM_ROWS, TEST_NUM_COLUMNS)
         );
+        assert_eq!(
+            Some((6, 1)),
+            part2_online(&coordinates, TEST_NUM_ROWS, TEST_NUM_COLUMNS)
+        );
+    }
+
+    #[test]
+    fn test_part2_matches_a_star() {
+        let coordinates = TEST_INPUT
+            .lines()
+            .map(|line| parse_coordinates(line).unwrap())
+            .collect::<Vec<_>>();
+
+        let mut connectivity = ByteConnectivity::new(TEST_NUM_ROWS, TEST_NUM_COLUMNS);
+        for (i, &coord) in coordinates.iter().enumerate() {
+            let reachable = part1(
+                coordinates.iter().copied(),
+                TEST_NUM_ROWS,
+                TEST_NUM_COLUMNS,
+                i + 1,
+            )
+            .is_some();
+            assert_eq!(
+                reachable,
+                connectivity.add_byte(coord),
+                "byte {i}: {coord:?}"
+            );
+        }
+
+        // a byte on the exit itself blocks it straight away
+        assert_eq!(
+            Some((6, 6)),
+            part2(&[(6, 6)], TEST_NUM_ROWS, TEST_NUM_COLUMNS)
+        );
+        assert_eq!(
+            Some((6, 6)),
+            part2_online(&[(6, 6)], TEST_NUM_ROWS, TEST_NUM_COLUMNS)
+        );
+        assert_eq!(None, part2(&[(3, 3)], TEST_NUM_ROWS, TEST_NUM_COLUMNS));
+    }
+
+    #[test]
+    fn test_part2_online_matches_offline() {
+        let input_coordinates = parse_bytes(include_str!("../input.txt"), 71, 71).unwrap();
+        assert_eq!(
+            part2(&input_coordinates, 71, 71),
+            part2_online(&input_coordinates, 71, 71)
+        );
+
+        let coordinates = TEST_INPUT
+            .lines()
+            .map(|line| parse_coordinates(line).unwrap())
+            .collect::<Vec<_>>();
+        for num_bytes in 0..=coordinates.len() {
+            assert_eq!(
+                part2(&coordinates[..num_bytes], TEST_NUM_ROWS, TEST_NUM_COLUMNS),
+                part2_online(&coordinates[..num_bytes], TEST_NUM_ROWS, TEST_NUM_COLUMNS),
+                "num_bytes = {num_bytes}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_falling_while_walking() {
+        let coordinates = TEST_INPUT
+            .lines()
+            .map(|line| parse_coordinates(line).unwrap());
+        let falling_bytes = FallingBytes::new(coordinates, TEST_NUM_ROWS, TEST_NUM_COLUMNS);
+
+        let (arrival_time, path) = falling_bytes.earliest_arrival().unwrap();
+
+        assert_eq!(12, arrival_time);
+        assert_eq!(arrival_time as usize + 1, path.len());
+        assert_eq!(Some(&(0, 0)), path.first());
+        assert_eq!(Some(&(6, 6)), path.last());
+        for (time, (&position, &next_position)) in path.iter().zip(path.iter().skip(1)).enumerate()
+        {
+            assert!(
+                position.0.abs_diff(next_position.0) + position.1.abs_diff(next_position.1) <= 1
+            );
+            assert_eq!(
+                GridCell::Safe,
+                falling_bytes.cell_at(next_position, time as u64 + 1)
+            );
+        }
+
+        // the first byte lands on the exit, so there's no way out
+        let falling_bytes = FallingBytes::new([(6, 6)], TEST_NUM_ROWS, TEST_NUM_COLUMNS);
+        assert_eq!(None, falling_bytes.earliest_arrival());
+    }
+
+    #[test]
+    fn test_render() {
+        let coordinates = TEST_INPUT
+            .lines()
+            .map(|line| parse_coordinates(line).unwrap());
+        let grid = corrupted_grid(
+            coordinates,
+            TEST_NUM_ROWS,
+            TEST_NUM_COLUMNS,
+            TEST_NUM_BYTES_TO_FALL,
+        );
+
+        assert_eq!(
+            "...#...\n\
+             ..#..#.\n\
+             ....#..\n\
+             ...#..#\n\
+             ..#..#.\n\
+             .#..#..\n\
+             #.#....",
+            render(&grid, &[])
+        );
+
+        let (cost, path) = shortest_path(&grid).unwrap();
+        let rendered = render(&grid, &path);
+        assert_eq!(cost as usize + 1, rendered.matches('O').count());
+        assert_eq!(
+            render(&grid, &[]).matches('#').count(),
+            rendered.matches('#').count()
+        );
+    }
+
+    #[test]
+    fn test_render_non_square() {
+        // 4 across and 2 down, with the path along the top and down the right
+        let grid = corrupted_grid([(1, 1), (2, 1)], 4, 2, 2);
+        assert_eq!("....\n.##.", render(&grid, &[]));
+
+        let (_, path) = shortest_path(&grid).unwrap();
+        assert_eq!("OOOO\n.##O", render(&grid, &path));
+    }
+
+    #[test]
+    fn test_config_from_args() {
+        let args = [
+            "--width", "9", "--height", "7", "--bytes", "12", "--render", "--online",
+        ];
+        let config = Config::from_args(args.map(String::from)).unwrap();
+        assert_eq!(
+            (9, 7, 12, true, false, true),
+            (
+                config.width,
+                config.height,
+                config.num_bytes_to_fall,
+                config.render,
+                config.falling,
+                config.online
+            )
+        );
+
+        assert!(Config::from_args(["--width".to_string()]).is_err());
+        assert!(Config::from_args(["--width", "seven"].map(String::from)).is_err());
+        assert!(Config::from_args(["--size".to_string()]).is_err());
+
+        let config = Config::from_args(["--input", "example.txt"].map(String::from)).unwrap();
+        assert_eq!(Some("example.txt".into()), config.input);
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(
+            TEST_INPUT
+                .lines()
+                .map(|line| parse_coordinates(line).unwrap())
+                .collect::<Vec<_>>(),
+            parse_bytes(TEST_INPUT, TEST_NUM_ROWS, TEST_NUM_COLUMNS).unwrap()
+        );
+
+        assert_eq!(
+            Err(puzzle_input::Error::ByteOutOfBounds(2, (7, 0))),
+            parse_bytes("1,2\n7,0\n", TEST_NUM_ROWS, TEST_NUM_COLUMNS)
+        );
+        assert_eq!(
+            Err(puzzle_input::Error::InvalidByte(2, "3;4".to_string())),
+            parse_bytes("1,2\n3;4\n", TEST_NUM_ROWS, TEST_NUM_COLUMNS)
+        );
     }
 }
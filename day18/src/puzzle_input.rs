@@ -0,0 +1,50 @@
+use std::fmt::{Display, Formatter};
+
+// line numbers start at 1, like in an editor
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    InvalidByte(usize, String),
+    ByteOutOfBounds(usize, (usize, usize)),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Error::*;
+        match self {
+            InvalidByte(line, value) => {
+                write!(f, "Line {line}: expected a byte like '5,4', got '{value}'")
+            }
+            ByteOutOfBounds(line, coord) => {
+                write!(f, "Line {line}: byte {coord:?} falls outside the grid")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub fn parse_coordinates(value: &str) -> Option<(usize, usize)> {
+    let (i_str, j_str) = value.trim().split_once(',')?;
+    Some((i_str.parse().ok()?, j_str.parse().ok()?))
+}
+
+// every byte has to land inside the grid
+pub fn parse_bytes(
+    input: &str,
+    num_rows: usize,
+    num_columns: usize,
+) -> Result<Vec<(usize, usize)>, Error> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let coord = parse_coordinates(line)
+                .ok_or_else(|| Error::InvalidByte(i + 1, line.to_string()))?;
+            if coord.0 < num_rows && coord.1 < num_columns {
+                Ok(coord)
+            } else {
+                Err(Error::ByteOutOfBounds(i + 1, coord))
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,42 @@
+// disjoint set forest with path halving and union by size
+pub struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl UnionFind {
+    pub fn new(num_elements: usize) -> Self {
+        UnionFind {
+            parents: (0..num_elements).collect(),
+            sizes: vec![1; num_elements],
+        }
+    }
+
+    pub fn find(&mut self, mut element: usize) -> usize {
+        while self.parents[element] != element {
+            self.parents[element] = self.parents[self.parents[element]];
+            element = self.parents[element];
+        }
+        element
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) {
+        let mut root_a = self.find(a);
+        let mut root_b = self.find(b);
+
+        if root_a == root_b {
+            return;
+        }
+
+        if self.sizes[root_a] < self.sizes[root_b] {
+            std::mem::swap(&mut root_a, &mut root_b);
+        }
+
+        self.parents[root_b] = root_a;
+        self.sizes[root_a] += self.sizes[root_b];
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
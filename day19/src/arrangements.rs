@@ -0,0 +1,135 @@
+use std::{
+    fmt::{Display, Formatter},
+    iter::from_fn,
+};
+
+use crate::towel_trie::TowelTrie;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TooManyWays;
+
+impl Display for TooManyWays {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "There are more than {} ways to make the designs",
+            u128::MAX
+        )
+    }
+}
+
+impl std::error::Error for TooManyWays {}
+
+// bottom up over the positions in the design, each entry describes
+// the ways to make design[i..]
+pub struct Arrangements<'a> {
+    towel_trie: &'a TowelTrie,
+    design: &'a [u8],
+    is_possible: Vec<bool>,
+    // None once the count no longer fits in a u128
+    num_ways: Vec<Option<u128>>,
+    fewest_towels: Vec<Option<usize>>,
+}
+
+impl<'a> Arrangements<'a> {
+    pub fn new(towel_trie: &'a TowelTrie, design: &'a [u8]) -> Self {
+        let n = design.len();
+
+        let mut is_possible = vec![false; n + 1];
+        let mut num_ways: Vec<Option<u128>> = vec![Some(0); n + 1];
+        let mut fewest_towels: Vec<Option<usize>> = vec![None; n + 1];
+
+        is_possible[n] = true;
+        num_ways[n] = Some(1);
+        fewest_towels[n] = Some(0);
+
+        for start in (0..n).rev() {
+            for end in towel_trie.towel_ends_at(design, start) {
+                if !is_possible[end] {
+                    continue;
+                }
+
+                is_possible[start] = true;
+                num_ways[start] = num_ways[start]
+                    .zip(num_ways[end])
+                    .and_then(|(a, b)| a.checked_add(b));
+
+                let num_towels = fewest_towels[end].unwrap() + 1;
+                if fewest_towels[start].is_none_or(|fewest| num_towels < fewest) {
+                    fewest_towels[start] = Some(num_towels);
+                }
+            }
+        }
+
+        Arrangements {
+            towel_trie,
+            design,
+            is_possible,
+            num_ways,
+            fewest_towels,
+        }
+    }
+
+    pub fn is_possible(&self) -> bool {
+        self.is_possible[0]
+    }
+
+    // None if there are more than u128::MAX ways
+    pub fn num_ways(&self) -> Option<u128> {
+        self.num_ways[0]
+    }
+
+    pub fn fewest_towels(&self) -> Option<usize> {
+        self.fewest_towels[0]
+    }
+
+    // one of the arrangements using the fewest towels
+    pub fn fewest_towels_arrangement(&self) -> Option<Vec<&'a [u8]>> {
+        let mut arrangement = Vec::with_capacity(self.fewest_towels()?);
+        let mut start = 0;
+
+        while start < self.design.len() {
+            let num_towels = self.fewest_towels[start]?;
+            let end = self
+                .towel_trie
+                .towel_ends_at(self.design, start)
+                .find(|&end| self.fewest_towels[end] == Some(num_towels - 1))?;
+            arrangement.push(&self.design[start..end]);
+            start = end;
+        }
+
+        Some(arrangement)
+    }
+
+    // every arrangement, depth first, without ever walking into a dead end.
+    // use take(n) to only enumerate the first n
+    pub fn iter(&self) -> impl '_ + Iterator<Item = Vec<&'a [u8]>> {
+        let mut stack = if self.is_possible() {
+            vec![(0, vec![])]
+        } else {
+            vec![]
+        };
+
+        from_fn(move || {
+            while let Some((start, arrangement)) = stack.pop() {
+                if start == self.design.len() {
+                    return Some(arrangement);
+                }
+
+                let ends = self
+                    .towel_trie
+                    .towel_ends_at(self.design, start)
+                    .filter(|&end| self.is_possible[end])
+                    .collect::<Vec<_>>();
+
+                for end in ends.into_iter().rev() {
+                    let mut next_arrangement = arrangement.clone();
+                    next_arrangement.push(&self.design[start..end]);
+                    stack.push((end, next_arrangement));
+                }
+            }
+
+            None
+        })
+    }
+}
@@ -1,99 +1,87 @@
-use std::collections::HashMap;
+mod arrangements;
+mod puzzle_input;
+mod towel_trie;
 
-fn parse_input(input_str: &str) -> (Vec<&[u8]>, Vec<&[u8]>) {
-    let mut lines = input_str.lines();
+use arrangements::{Arrangements, TooManyWays};
+use puzzle_input::parse_input;
+use towel_trie::TowelTrie;
 
-    let towels = lines
-        .next()
-        .unwrap()
-        .split(", ")
-        .map(str::as_bytes)
-        .collect();
+fn part1(towels: &[&[u8]], designs: &[&[u8]]) -> usize {
+    let towel_trie = TowelTrie::new(towels.iter().copied());
 
-    lines.next();
+    designs
+        .iter()
+        .filter(|design| towel_trie.can_make(design))
+        .count()
+}
 
-    let designs = lines.map(str::as_bytes).collect();
+fn part2(towels: &[&[u8]], designs: &[&[u8]]) -> Result<u128, TooManyWays> {
+    let towel_trie = TowelTrie::new(towels.iter().copied());
 
-    (towels, designs)
+    designs.iter().try_fold(0u128, |total, design| {
+        Arrangements::new(&towel_trie, design)
+            .num_ways()
+            .and_then(|num_ways| total.checked_add(num_ways))
+            .ok_or(TooManyWays)
+    })
 }
 
-fn num_ways_to_make_design<'a>(
-    towels: &[&[u8]],
-    design: &'a [u8],
-    memoization: &mut HashMap<&'a [u8], usize>,
-) -> usize {
-    if let Some(&num_ways) = memoization.get(design) {
-        return num_ways;
-    };
-
-    let result = if design.is_empty() {
-        1
-    } else {
-        towels
-            .iter()
-            .map(|&towel| {
-                design
-                    .strip_prefix(towel)
-                    .map(|rest| num_ways_to_make_design(towels, rest, memoization))
-                    .unwrap_or(0)
-            })
-            .sum()
-    };
-
-    memoization.insert(design, result);
-
-    result
+fn format_arrangement(arrangement: &[&[u8]]) -> String {
+    arrangement
+        .iter()
+        .map(|towel| String::from_utf8_lossy(towel))
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
-fn is_design_possible<'a>(
-    towels: &[&[u8]],
-    design: &'a [u8],
-    memoization: &mut HashMap<&'a [u8], bool>,
-) -> bool {
-    if let Some(&is_possible) = memoization.get(design) {
-        return is_possible;
-    };
-
-    let result = if design.is_empty() {
-        true
-    } else {
-        towels.iter().any(|&towel| {
-            design
-                .strip_prefix(towel)
-                .map(|rest| is_design_possible(towels, rest, memoization))
-                .unwrap_or(false)
-        })
-    };
-
-    memoization.insert(design, result);
-    result
-}
+fn print_arrangements(towels: &[&[u8]], designs: &[&[u8]]) {
+    const MAX_ARRANGEMENTS_SHOWN: usize = 2;
 
-fn part1(towels: &[&[u8]], designs: &[&[u8]]) -> usize {
-    let mut memoization: HashMap<&[u8], bool> = HashMap::new();
+    let towel_trie = TowelTrie::new(towels.iter().copied());
 
-    designs
-        .iter()
-        .filter(|&design| is_design_possible(towels, design, &mut memoization))
-        .count()
-}
+    for design in designs {
+        let arrangements = Arrangements::new(&towel_trie, design);
+        let design = String::from_utf8_lossy(design);
 
-fn part2(towels: &[&[u8]], designs: &[&[u8]]) -> usize {
-    let mut memoization: HashMap<&[u8], usize> = HashMap::new();
+        let Some(fewest_towels_arrangement) = arrangements.fewest_towels_arrangement() else {
+            println!("{design}: impossible");
+            continue;
+        };
 
-    designs
-        .iter()
-        .map(|design| num_ways_to_make_design(towels, design, &mut memoization))
-        .sum()
+        let num_ways = match arrangements.num_ways() {
+            Some(num_ways) => num_ways.to_string(),
+            None => format!("more than {}", u128::MAX),
+        };
+
+        let examples = arrangements
+            .iter()
+            .take(MAX_ARRANGEMENTS_SHOWN)
+            .map(|arrangement| format_arrangement(&arrangement))
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        println!(
+            "{design}: {num_ways} ways, fewest {} towels ({}), e.g. {examples}",
+            fewest_towels_arrangement.len(),
+            format_arrangement(&fewest_towels_arrangement),
+        );
+    }
 }
 
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     const INPUT_STR: &str = include_str!("../input.txt");
 
-    let (towels, designs) = parse_input(INPUT_STR);
+    let (towels, designs) = parse_input(INPUT_STR)?;
 
     println!("{}", part1(&towels, &designs));
-    println!("{}", part2(&towels, &designs));
+    println!("{}", part2(&towels, &designs)?);
+
+    // --arrangements lists how each design can be made
+    if std::env::args().skip(1).any(|arg| arg == "--arrangements") {
+        print_arrangements(&towels, &designs);
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -104,22 +92,113 @@ mod tests {
 
     #[test]
     fn test_parse_input() {
-        let (towels, designs) = parse_input(INPUT_STR);
+        let (towels, designs) = parse_input(INPUT_STR).unwrap();
         assert_eq!(towels.len(), 8);
         assert_eq!(designs.len(), 8);
     }
 
     #[test]
     fn test_part1() {
-        let (towels, designs) = parse_input(INPUT_STR);
+        let (towels, designs) = parse_input(INPUT_STR).unwrap();
 
         assert_eq!(part1(&towels, &designs), 6);
     }
 
     #[test]
     fn test_part2() {
-        let (towels, designs) = parse_input(INPUT_STR);
+        let (towels, designs) = parse_input(INPUT_STR).unwrap();
+
+        assert_eq!(part2(&towels, &designs), Ok(16));
+    }
 
-        assert_eq!(part2(&towels, &designs), 16);
+    #[test]
+    fn test_long_design() {
+        let towels: [&[u8]; 2] = [b"r", b"rr"];
+
+        // far deeper than the old recursion could go
+        let design = vec![b'r'; 1_000_000];
+        assert_eq!(part1(&towels, &[&design]), 1);
+
+        // fibonacci, since the design either starts with r or rr
+        let design = [b'r'; 80];
+        assert_eq!(part2(&towels, &[&design]), Ok(37889062373143906));
+
+        // fibonacci(201) is past u128::MAX, and so are two lots of fibonacci(186)
+        let design = [b'r'; 200];
+        assert_eq!(part2(&towels, &[&design]), Err(TooManyWays));
+        let design = [b'r'; 185];
+        assert_eq!(part2(&towels, &[&design, &design]), Err(TooManyWays));
+    }
+
+    #[test]
+    fn test_arrangements() {
+        let (towels, _) = parse_input(INPUT_STR).unwrap();
+        let towel_trie = TowelTrie::new(towels.iter().copied());
+
+        let arrangements = Arrangements::new(&towel_trie, b"gbbr");
+        let all_arrangements = arrangements.iter().collect::<Vec<_>>();
+        assert_eq!(Some(4), arrangements.num_ways());
+        assert_eq!(4, all_arrangements.len());
+        assert_eq!(2, arrangements.iter().take(2).count());
+        for arrangement in all_arrangements {
+            assert_eq!(b"gbbr".to_vec(), arrangement.concat());
+        }
+
+        assert_eq!(Some(2), arrangements.fewest_towels());
+        assert_eq!(
+            Some(vec![b"gb".as_slice(), b"br".as_slice()]),
+            arrangements.fewest_towels_arrangement()
+        );
+
+        let arrangements = Arrangements::new(&towel_trie, b"ubwu");
+        assert!(!arrangements.is_possible());
+        assert!(!towel_trie.can_make(b"ubwu"));
+        assert!(towel_trie.can_make(b"gbbr"));
+        assert_eq!(Some(0), arrangements.num_ways());
+        assert_eq!(None, arrangements.fewest_towels_arrangement());
+        assert_eq!(0, arrangements.iter().count());
+
+        // fibonacci(151) is well past u64::MAX
+        let towels: [&[u8]; 2] = [b"r", b"rr"];
+        let towel_trie = TowelTrie::new(towels);
+        let design = [b'r'; 150];
+        let arrangements = Arrangements::new(&towel_trie, &design);
+        assert_eq!(
+            Some(16130531424904581415797907386349),
+            arrangements.num_ways()
+        );
+        assert_eq!(Some(75), arrangements.fewest_towels());
+
+        // fibonacci(201) is past u128::MAX
+        let design = [b'r'; 200];
+        assert_eq!(None, Arrangements::new(&towel_trie, &design).num_ways());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        use puzzle_input::Error;
+
+        let crlf_input = INPUT_STR.replace('\n', "\r\n") + "\r\n\r\n";
+        assert_eq!(parse_input(INPUT_STR), parse_input(&crlf_input));
+
+        assert_eq!(Err(Error::NoTowels), parse_input(""));
+        assert_eq!(Err(Error::MissingSeparator(2)), parse_input("r, wr\nbrwrr"));
+        assert_eq!(Err(Error::EmptyTowel(1)), parse_input("r, , wr\n\nbrwrr"));
+        assert_eq!(
+            Err(Error::DuplicateTowel(1, "wr".to_string())),
+            parse_input("r, wr, b, wr\n\nbrwrr")
+        );
+        assert_eq!(
+            Err(Error::UnrecognizedStripe(1, 4, 'x')),
+            parse_input("r, xr\n\nbrwrr")
+        );
+        assert_eq!(
+            Err(Error::UnrecognizedStripe(4, 3, 'y')),
+            parse_input("r, wr\n\nbrwrr\nbry")
+        );
+        assert_eq!(
+            Err(Error::EmptyDesign(4)),
+            parse_input("r, wr\n\nbrwrr\n\nbrwrr")
+        );
     }
 }
@@ -0,0 +1,111 @@
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+};
+
+const STRIPE_COLORS: &[u8] = b"wubrg";
+
+// line and column numbers start at 1, like in an editor
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    NoTowels,
+    MissingSeparator(usize),
+    EmptyTowel(usize),
+    DuplicateTowel(usize, String),
+    EmptyDesign(usize),
+    UnrecognizedStripe(usize, usize, char),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Error::*;
+        match self {
+            NoTowels => write!(f, "The input is empty, expected a list of towels"),
+            MissingSeparator(line) => write!(
+                f,
+                "Line {line}: expected a blank line between the towels and the designs"
+            ),
+            EmptyTowel(line) => write!(f, "Line {line}: found an empty towel"),
+            DuplicateTowel(line, towel) => {
+                write!(f, "Line {line}: towel '{towel}' appears more than once")
+            }
+            EmptyDesign(line) => write!(f, "Line {line}: found an empty design"),
+            UnrecognizedStripe(line, column, c) => write!(
+                f,
+                "Line {line}, column {column}: '{c}' is not a stripe colour (w, u, b, r or g)"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+fn check_stripes(line_number: usize, start_column: usize, stripes: &str) -> Result<(), Error> {
+    match stripes
+        .char_indices()
+        .find(|&(_, c)| !c.is_ascii() || !STRIPE_COLORS.contains(&(c as u8)))
+    {
+        Some((i, c)) => Err(Error::UnrecognizedStripe(line_number, start_column + i, c)),
+        None => Ok(()),
+    }
+}
+
+pub type TowelsAndDesigns<'a> = (Vec<&'a [u8]>, Vec<&'a [u8]>);
+
+// towels separated by commas on the first line, a blank line, then one
+// design per line. Windows line endings, trailing whitespace and trailing
+// blank lines are all fine.
+pub fn parse_input(input_str: &str) -> Result<TowelsAndDesigns<'_>, Error> {
+    let mut lines = input_str
+        .lines()
+        .map(str::trim_end)
+        .enumerate()
+        .map(|(i, line)| (i + 1, line));
+
+    let (towels_line_number, towels_line) = lines.next().ok_or(Error::NoTowels)?;
+
+    let mut seen_towels = HashSet::new();
+    let mut towels = Vec::new();
+    let mut column = 1;
+
+    for towel_with_spaces in towels_line.split(',') {
+        let towel = towel_with_spaces.trim();
+        let towel_column =
+            column + (towel_with_spaces.len() - towel_with_spaces.trim_start().len());
+        column += towel_with_spaces.len() + 1;
+
+        if towel.is_empty() {
+            return Err(Error::EmptyTowel(towels_line_number));
+        }
+        check_stripes(towels_line_number, towel_column, towel)?;
+        if !seen_towels.insert(towel) {
+            return Err(Error::DuplicateTowel(towels_line_number, towel.to_string()));
+        }
+
+        towels.push(towel.as_bytes());
+    }
+
+    match lines.next() {
+        Some((_, "")) | None => (),
+        Some((line_number, _)) => return Err(Error::MissingSeparator(line_number)),
+    }
+
+    let design_lines = lines.collect::<Vec<_>>();
+    let num_designs = design_lines
+        .iter()
+        .rposition(|&(_, line)| !line.is_empty())
+        .map_or(0, |i| i + 1);
+
+    let designs = design_lines[..num_designs]
+        .iter()
+        .map(|&(line_number, design)| {
+            if design.is_empty() {
+                return Err(Error::EmptyDesign(line_number));
+            }
+            check_stripes(line_number, 1, design)?;
+            Ok(design.as_bytes())
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((towels, designs))
+}
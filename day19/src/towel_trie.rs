@@ -0,0 +1,77 @@
+#[derive(Default)]
+struct TrieNode {
+    // at most one child per stripe colour, so a linear scan is plenty
+    children: Vec<(u8, usize)>,
+    is_towel_end: bool,
+}
+
+pub struct TowelTrie {
+    nodes: Vec<TrieNode>,
+}
+
+impl TowelTrie {
+    pub fn new<'a>(towels: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        let mut trie = TowelTrie {
+            nodes: vec![TrieNode::default()],
+        };
+
+        for towel in towels {
+            let mut node = 0;
+            for &stripe in towel {
+                node = match trie.child(node, stripe) {
+                    Some(child) => child,
+                    None => {
+                        trie.nodes.push(TrieNode::default());
+                        let child = trie.nodes.len() - 1;
+                        trie.nodes[node].children.push((stripe, child));
+                        child
+                    }
+                };
+            }
+            trie.nodes[node].is_towel_end = true;
+        }
+
+        trie
+    }
+
+    fn child(&self, node: usize, stripe: u8) -> Option<usize> {
+        self.nodes[node]
+            .children
+            .iter()
+            .find(|&&(child_stripe, _)| child_stripe == stripe)
+            .map(|&(_, child)| child)
+    }
+
+    // every index end such that design[start..end] is a towel, in increasing order
+    pub fn towel_ends_at<'a>(
+        &'a self,
+        design: &'a [u8],
+        start: usize,
+    ) -> impl 'a + Iterator<Item = usize> {
+        design[start..]
+            .iter()
+            .scan(0, |node, &stripe| {
+                *node = self.child(*node, stripe)?;
+                Some(*node)
+            })
+            .enumerate()
+            .filter(|&(_, node)| self.nodes[node].is_towel_end)
+            .map(move |(i, _)| start + i + 1)
+    }
+
+    // whether the design can be made at all, bottom up over its positions
+    // and stopping at the first towel that leads somewhere
+    pub fn can_make(&self, design: &[u8]) -> bool {
+        let n = design.len();
+        let mut is_possible = vec![false; n + 1];
+        is_possible[n] = true;
+
+        for start in (0..n).rev() {
+            is_possible[start] = self
+                .towel_ends_at(design, start)
+                .any(|end| is_possible[end]);
+        }
+
+        is_possible[0]
+    }
+}
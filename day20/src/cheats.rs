@@ -0,0 +1,133 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use array2d::Array2D;
+use enum_iterator::all;
+
+use crate::{
+    direction::Direction, grid_cell::GridCell, manhattan_index::ManhattanIndex,
+    puzzle_input::PuzzleInput,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cheat {
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+    pub length: usize,
+    pub saving: usize,
+}
+
+fn move_once_in_direction(
+    (i, j): (usize, usize),
+    direction: Direction,
+    num_rows: usize,
+    num_columns: usize,
+) -> Option<(usize, usize)> {
+    let (di, dj) = direction.as_tuple();
+
+    let new_i = i.checked_add_signed(di)?;
+    let new_j = j.checked_add_signed(dj)?;
+
+    if new_i >= num_rows || new_j >= num_columns {
+        None
+    } else {
+        Some((new_i, new_j))
+    }
+}
+
+// breadth first search over the track from source,
+// None for walls and for track that can't be reached
+fn get_distance_grid(grid: &Array2D<GridCell>, source: (usize, usize)) -> Array2D<Option<usize>> {
+    let mut dist_grid = Array2D::filled_with(None, grid.num_rows(), grid.num_columns());
+    let mut frontier = VecDeque::from([source]);
+    dist_grid[source] = Some(0);
+
+    while let Some(position) = frontier.pop_front() {
+        let distance = dist_grid[position].unwrap();
+
+        for direction in all::<Direction>() {
+            let Some(next_position) =
+                move_once_in_direction(position, direction, grid.num_rows(), grid.num_columns())
+            else {
+                continue;
+            };
+
+            if grid[next_position] == GridCell::Track && dist_grid[next_position].is_none() {
+                dist_grid[next_position] = Some(distance + 1);
+                frontier.push_back(next_position);
+            }
+        }
+    }
+
+    dist_grid
+}
+
+pub struct CheatFinder {
+    dist_from_start: Array2D<Option<usize>>,
+    dist_from_end: Array2D<Option<usize>>,
+    best_time: usize,
+    track_index: ManhattanIndex,
+}
+
+impl CheatFinder {
+    // None if the end can't be reached from the start without cheating
+    pub fn new(puzzle_input: &PuzzleInput) -> Option<Self> {
+        let dist_from_start = get_distance_grid(&puzzle_input.grid, puzzle_input.start_position);
+        let dist_from_end = get_distance_grid(&puzzle_input.grid, puzzle_input.end_position);
+
+        let best_time = dist_from_start[puzzle_input.end_position]?;
+
+        let track_index = ManhattanIndex::new(
+            dist_from_end
+                .enumerate_row_major()
+                .filter_map(|(position, maybe_dist)| maybe_dist.map(|_| position)),
+            dist_from_end.num_rows(),
+            dist_from_end.num_columns(),
+        );
+
+        Some(CheatFinder {
+            dist_from_start,
+            dist_from_end,
+            best_time,
+            track_index,
+        })
+    }
+
+    // every cheat lasting at most max_cheat_length that saves some time.
+    // From instructions:
+    // "cheats are uniquely identified by their start position and end position"
+    // So, only need to check the track positions where a cheat starts and ends,
+    // can ignore checking walls and stuff
+    pub fn cheats(&self, max_cheat_length: usize) -> impl '_ + Iterator<Item = Cheat> {
+        self.track_index
+            .points()
+            .iter()
+            .filter_map(|&start| self.dist_from_start[start].map(|dist| (start, dist)))
+            .flat_map(move |(start, dist_before_cheat)| {
+                self.track_index
+                    .within(start, max_cheat_length)
+                    .filter_map(move |(end, length)| {
+                        let dist_after_cheat = self.dist_from_end[end].unwrap();
+                        let time_with_cheat = dist_before_cheat + length + dist_after_cheat;
+                        let saving = self.best_time.checked_sub(time_with_cheat)?;
+
+                        (saving > 0).then_some(Cheat {
+                            start,
+                            end,
+                            length,
+                            saving,
+                        })
+                    })
+            })
+    }
+}
+
+// saving -> number of cheats with that saving
+pub fn savings_histogram(cheats: impl IntoIterator<Item = Cheat>) -> BTreeMap<usize, usize> {
+    let mut histogram = BTreeMap::new();
+
+    for cheat in cheats {
+        *histogram.entry(cheat.saving).or_insert(0) += 1;
+    }
+
+    histogram
+}
@@ -0,0 +1,54 @@
+use array2d::Array2D;
+
+// all the points that make up the track, laid out so the points within
+// a manhattan radius of any position can be read off directly by walking
+// the diamond around it
+pub struct ManhattanIndex {
+    is_point: Array2D<bool>,
+    points: Vec<(usize, usize)>,
+}
+
+impl ManhattanIndex {
+    pub fn new(
+        points: impl IntoIterator<Item = (usize, usize)>,
+        num_rows: usize,
+        num_columns: usize,
+    ) -> Self {
+        let mut is_point = Array2D::filled_with(false, num_rows, num_columns);
+        let points: Vec<_> = points.into_iter().collect();
+
+        for &point in points.iter() {
+            is_point[point] = true;
+        }
+
+        ManhattanIndex { is_point, points }
+    }
+
+    pub fn points(&self) -> &[(usize, usize)] {
+        &self.points
+    }
+
+    // every point at manhattan distance at most radius from center,
+    // along with its distance
+    pub fn within(
+        &self,
+        center: (usize, usize),
+        radius: usize,
+    ) -> impl '_ + Iterator<Item = ((usize, usize), usize)> {
+        let (i, j) = center;
+
+        let min_i = i.saturating_sub(radius);
+        let max_i = (i + radius).min(self.is_point.num_rows() - 1);
+
+        (min_i..=max_i).flat_map(move |new_i| {
+            let remaining = radius - new_i.abs_diff(i);
+            let min_j = j.saturating_sub(remaining);
+            let max_j = (j + remaining).min(self.is_point.num_columns() - 1);
+
+            (min_j..=max_j)
+                .map(move |new_j| (new_i, new_j))
+                .filter(|&position| self.is_point[position])
+                .map(move |position| (position, new_i.abs_diff(i) + position.1.abs_diff(j)))
+        })
+    }
+}
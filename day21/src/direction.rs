@@ -8,6 +8,13 @@ pub enum Direction {
 }
 
 impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
     pub fn as_tuple(&self) -> (isize, isize) {
         match self {
             Direction::North => (-1, 0),
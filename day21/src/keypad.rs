@@ -1,14 +1,20 @@
-use std::iter::once;
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    iter::once,
+    str::FromStr,
+};
 
 use array2d::Array2D;
 use itertools::Itertools;
-use memoize::memoize;
-use once_cell::sync::Lazy;
 
 use crate::{
-    direction::Direction,
+    direction::{move_once_checked, Direction},
     instruction::Instruction,
+    press_sequence::PressSequence,
+    search::best_path_djikstra,
     shortest_paths::{iter_positions, BestPaths},
+    simulator::{SimulationError, Simulator},
 };
 
 #[derive(Debug, Clone)]
@@ -23,12 +29,20 @@ impl<T: Eq> Keypad<T> {
             .0
     }
 
+    pub fn contains(&self, position: (usize, usize)) -> bool {
+        position.0 < self.0.num_rows() && position.1 < self.0.num_columns()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &T> {
+        self.0.elements_row_major_iter().flatten()
+    }
+
+    // None for gaps and for positions off the keypad
     pub fn get(&self, position: (usize, usize)) -> Option<&T> {
-        self.0[position].as_ref()
+        self.0.get(position.0, position.1)?.as_ref()
     }
 
-    // assume the best solution contains only shortest paths for each robot
-    // and the best solution contains only shortest paths with at most 1 turn
+    // shortest paths on the grid with at most 1 turn that avoid the gaps
     fn get_best_paths(
         &self,
         current_position: (usize, usize),
@@ -62,34 +76,6 @@ impl<T: Eq> Keypad<T> {
                 .collect()
         })
     }
-
-    // return all possible best instructions needed to input next_input
-    // starting from start_key
-    pub fn get_successors_for_input_sequence(
-        &self,
-        start_key: &T,
-        sequence_to_input: &[T],
-    ) -> Vec<Vec<Instruction>> {
-        let mut best_paths: Vec<Vec<Instruction>> = vec![vec![]];
-
-        for (prev_key, next_key) in once(start_key)
-            .chain(sequence_to_input.iter())
-            .tuple_windows()
-        {
-            let succs = self.get_successors(prev_key, next_key);
-            best_paths = succs
-                .into_iter()
-                .flat_map(|next_path_part| {
-                    best_paths
-                        .iter()
-                        .map(|best_path| [best_path.as_slice(), next_path_part.as_slice()].concat())
-                        .collect::<Vec<_>>()
-                })
-                .collect();
-        }
-
-        best_paths
-    }
 }
 
 impl<T> From<Array2D<Option<T>>> for Keypad<T> {
@@ -98,69 +84,282 @@ impl<T> From<Array2D<Option<T>>> for Keypad<T> {
     }
 }
 
-static INSTRUCTION_KEYPAD: Lazy<Keypad<Instruction>> = Lazy::new(|| {
-    use Direction::*;
-    Array2D::from_rows(&[
-        vec![None, Some(North.into()), Some(Instruction::Activate)],
-        vec![Some(West.into()), Some(South.into()), Some(East.into())],
-    ])
-    .unwrap()
-    .into()
-});
-
-static NUMERIC_KEYPAD: Lazy<Keypad<u8>> = Lazy::new(|| {
-    Array2D::from_iter_row_major(
-        b"789456123 0A"
-            .iter()
-            .map(|&c| if c == b' ' { None } else { Some(c) }),
-        4,
-        3,
-    )
-    .unwrap()
-    .into()
-});
-
-// robot_num = closest to numeric keypad is largest, 0 is me
-// return the number of instructions it takes for the robot
-
-// I high key have no idea what going on... need to redo this day
-
-// I realize it because each press starts + ends at activate :O
-#[memoize]
-fn shortest_length_helper(sequence_to_input: Vec<Instruction>, robot_num: usize) -> usize {
-    let mut result = 0;
-
-    for (prev_key, next_key) in once(Instruction::Activate)
-        .chain(sequence_to_input.into_iter())
-        .tuple_windows()
-    {
-        if robot_num == 0 {
-            result += INSTRUCTION_KEYPAD
-                .get_successors(&prev_key, &next_key)
-                .into_iter()
-                .next()
-                .unwrap()
-                .len();
-        } else {
-            result += INSTRUCTION_KEYPAD
-                .get_successors(&prev_key, &next_key)
-                .into_iter()
-                .map(|path| shortest_length_helper(path, robot_num - 1))
-                .min()
-                .unwrap();
+#[derive(Debug)]
+pub enum Error {
+    EmptyLayout,
+    UnrecognizedKey(char),
+    DuplicateKey(char),
+    Array2D(array2d::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Error::*;
+        match self {
+            EmptyLayout => write!(f, "Keypad layout contains no keys"),
+            UnrecognizedKey(c) => write!(f, "Found unrecognized key '{c}' in the keypad layout"),
+            DuplicateKey(c) => write!(f, "Key '{c}' appears more than once in the keypad layout"),
+            Array2D(error) => write!(f, "Received error when parsing into Array2D: '{error}'"),
         }
     }
+}
+
+// one row of keys per line, a space marks a gap
+// short lines are padded with gaps on the right
+impl<T: TryFrom<char> + PartialEq> FromStr for Keypad<T> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let num_columns = s.lines().map(|line| line.chars().count()).max();
+        let num_columns = num_columns.filter(|&n| n > 0).ok_or(Error::EmptyLayout)?;
 
-    result
+        let mut seen_keys = Vec::new();
+
+        let rows = s
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .chain(std::iter::repeat(' '))
+                    .take(num_columns)
+                    .map(|c| {
+                        if c == ' ' {
+                            return Ok(None);
+                        }
+                        let key = T::try_from(c).map_err(|_| Error::UnrecognizedKey(c))?;
+                        if seen_keys.contains(&c) {
+                            return Err(Error::DuplicateKey(c));
+                        }
+                        seen_keys.push(c);
+                        Ok(Some(key))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if seen_keys.is_empty() {
+            return Err(Error::EmptyLayout);
+        }
+
+        let num_rows = rows.len();
+        Ok(
+            Array2D::from_iter_row_major(rows.into_iter().flatten(), num_rows, num_columns)
+                .map_err(Error::Array2D)?
+                .into(),
+        )
+    }
+}
+
+// (depth, prev_key, next_key) -> (number of human presses, best path on the next keypad)
+pub type BestPathCache = HashMap<(usize, Instruction, Instruction), (usize, Vec<Instruction>)>;
+
+// (position of the arm, key under the arm one keypad closer to the human, pressed yet)
+type ArmState = ((usize, usize), Instruction, bool);
+
+// the door keypad is operated by a robot whose arm is driven by
+// robot_keypads[0], whose robot is driven by robot_keypads[1], and so on.
+// the last keypad in the chain is driven by the human directly.
+pub struct KeypadChain<T> {
+    door_keypad: Keypad<T>,
+    door_start_key: T,
+    robot_keypads: Vec<Keypad<Instruction>>,
+    cache: BestPathCache,
 }
 
-pub fn shortest_sequence_length(keycode: &[u8], num_intermediate_robots: usize) -> usize {
-    let best_paths_for_numeric_bot =
-        NUMERIC_KEYPAD.get_successors_for_input_sequence(&b'A', keycode);
+impl<T: Eq> KeypadChain<T> {
+    pub fn new(
+        door_keypad: Keypad<T>,
+        door_start_key: T,
+        robot_keypads: Vec<Keypad<Instruction>>,
+    ) -> Self {
+        let mut chain = KeypadChain {
+            door_keypad,
+            door_start_key,
+            robot_keypads,
+            cache: HashMap::new(),
+        };
+
+        // each depth only needs the costs of the depth after it
+        for depth in (0..chain.robot_keypads.len()).rev() {
+            let keypad = &chain.robot_keypads[depth];
+            let keys = keypad.keys().copied().collect_vec();
+            let best_paths = keys
+                .iter()
+                .cartesian_product(keys.iter())
+                .filter_map(|(&prev_key, &next_key)| {
+                    let best_path = chain.cheapest_path(
+                        keypad,
+                        depth + 1,
+                        keypad.position_of(&prev_key),
+                        keypad.position_of(&next_key),
+                    )?;
+                    Some(((depth, prev_key, next_key), best_path))
+                })
+                .collect_vec();
+            chain.cache.extend(best_paths);
+        }
+
+        chain
+    }
+
+    // number of human presses to move the arm over robot_keypads[depth]
+    // from prev_key to next_key and press it.
+    // every robot arm above depth starts and ends on Activate, so this
+    // only depends on the two keys.
+    // None if next_key can't be reached without going over a gap
+    fn key_press_cost(
+        &self,
+        depth: usize,
+        prev_key: Instruction,
+        next_key: Instruction,
+    ) -> Option<usize> {
+        if depth == self.robot_keypads.len() {
+            return Some(1);
+        }
+
+        self.cache
+            .get(&(depth, prev_key, next_key))
+            .map(|(cost, _)| *cost)
+    }
+
+    // number of human presses to type sequence on robot_keypads[depth],
+    // starting from Activate
+    fn sequence_cost(&self, depth: usize, sequence: &[Instruction]) -> Option<usize> {
+        once(Instruction::Activate)
+            .chain(sequence.iter().copied())
+            .tuple_windows()
+            .map(|(prev_key, next_key)| self.key_press_cost(depth, prev_key, next_key))
+            .sum()
+    }
+
+    // cheapest way to move the arm over keypad from start to target and press it,
+    // where each instruction is typed on the keypad at next_depth.
+    // searches every shortest path on the grid, however many turns it takes,
+    // so each move has to bring the arm closer to target.
+    // None if the gaps block every shortest path
+    fn cheapest_path<K: Eq>(
+        &self,
+        keypad: &Keypad<K>,
+        next_depth: usize,
+        start: (usize, usize),
+        target: (usize, usize),
+    ) -> Option<(usize, Vec<Instruction>)> {
+        let distance_to_target =
+            |(row, column): (usize, usize)| row.abs_diff(target.0) + column.abs_diff(target.1);
+
+        let successors = |&(position, arm_key, _): &ArmState| {
+            let moves = Direction::ALL.into_iter().filter_map(move |direction| {
+                let next_position = move_once_checked(position, direction)?;
+                keypad.get(next_position)?;
+                if distance_to_target(next_position) > distance_to_target(position) {
+                    return None;
+                }
+                let instruction = direction.into();
+                let cost = self.key_press_cost(next_depth, arm_key, instruction)?;
+                Some((cost, (next_position, instruction, false)))
+            });
+
+            let press = (position == target)
+                .then(|| self.key_press_cost(next_depth, arm_key, Instruction::Activate))
+                .flatten()
+                .map(|cost| (cost, (position, Instruction::Activate, true)));
+
+            moves.chain(press)
+        };
+
+        let (cost, path) = best_path_djikstra(
+            (start, Instruction::Activate, false),
+            successors,
+            |&(_, _, pressed)| pressed,
+        )?;
+
+        Some((
+            cost,
+            path.into_iter().skip(1).map(|(_, key, _)| key).collect(),
+        ))
+    }
+
+    // best path for the door robot to type each key of keycode,
+    // along with the number of human presses it costs
+    fn door_paths(&self, keycode: &[T]) -> Vec<(usize, Vec<Instruction>)> {
+        once(&self.door_start_key)
+            .chain(keycode.iter())
+            .tuple_windows()
+            .map(|(prev_key, next_key)| {
+                self.cheapest_path(
+                    &self.door_keypad,
+                    0,
+                    self.door_keypad.position_of(prev_key),
+                    self.door_keypad.position_of(next_key),
+                )
+                .expect("every key on the door keypad should be reachable along a shortest path")
+            })
+            .collect()
+    }
+
+    pub fn shortest_sequence_length(&self, keycode: &[T]) -> usize {
+        self.door_paths(keycode)
+            .into_iter()
+            .map(|(cost, _)| cost)
+            .sum()
+    }
 
-    best_paths_for_numeric_bot
-        .into_iter()
-        .map(|path| shortest_length_helper(path, num_intermediate_robots - 1))
-        .min()
-        .unwrap()
+    // the presses the human makes for one of the shortest sequences,
+    // generated lazily since it can be astronomically long
+    pub fn shortest_sequence(&self, keycode: &[T]) -> PressSequence<'_> {
+        let door_sequence = self
+            .door_paths(keycode)
+            .into_iter()
+            .flat_map(|(_, path)| path)
+            .collect();
+
+        PressSequence::new(&self.cache, self.robot_keypads.len(), door_sequence)
+    }
+
+    // transitions on the robot keypads where the best path with at most one turn
+    // costs more than the exact best path, as (depth, prev_key, next_key).
+    // empty for the stock keypads, where optimal paths never zig-zag
+    pub fn one_turn_shortcut_failures(&self) -> Vec<(usize, Instruction, Instruction)> {
+        self.cache
+            .iter()
+            .filter(|(&(depth, prev_key, next_key), &(cost, _))| {
+                let one_turn_cost = self.robot_keypads[depth]
+                    .get_successors(&prev_key, &next_key)
+                    .into_iter()
+                    .filter_map(|path| self.sequence_cost(depth + 1, &path))
+                    .min();
+                one_turn_cost != Some(cost)
+            })
+            .map(|(&key, _)| key)
+            .sorted_by_key(|&(depth, prev_key, next_key)| {
+                (depth, char::from(prev_key), char::from(next_key))
+            })
+            .collect()
+    }
+
+    // run the human's presses through every keypad in the chain,
+    // returning the keys typed on the door keypad
+    pub fn simulate(
+        &self,
+        presses: impl IntoIterator<Item = Instruction>,
+    ) -> Result<Vec<T>, SimulationError>
+    where
+        T: Clone,
+    {
+        Simulator::new(&self.door_keypad, &self.door_start_key, &self.robot_keypads).run(presses)
+    }
+
+    pub fn simulate_str(&self, presses: &str) -> Result<Vec<T>, SimulationError>
+    where
+        T: Clone,
+    {
+        let presses = presses
+            .chars()
+            .enumerate()
+            .map(|(step, c)| {
+                Instruction::try_from(c).map_err(|c| SimulationError::UnrecognizedPress { step, c })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.simulate(presses)
+    }
 }
@@ -1,9 +1,13 @@
 mod direction;
 mod instruction;
 mod keypad;
+mod press_sequence;
+mod search;
 mod shortest_paths;
+mod simulator;
 
-use keypad::shortest_sequence_length;
+use instruction::Instruction;
+use keypad::{Keypad, KeypadChain};
 
 fn parse_keycodes(input_str: &str) -> Vec<&[u8]> {
     input_str.lines().map(str::as_bytes).collect()
@@ -17,36 +21,230 @@ fn get_numeric_part_of_code(keycode: &[u8]) -> usize {
         .fold(0, |acc, val| acc * 10 + val as usize)
 }
 
-fn run(keypad_codes: &[&[u8]], num_intermediate_robots: usize) -> usize {
+fn run(keypad_codes: &[&[u8]], chain: &KeypadChain<u8>) -> usize {
     keypad_codes
         .iter()
-        .map(|&code| {
-            shortest_sequence_length(code, num_intermediate_robots) * get_numeric_part_of_code(code)
-        })
+        .map(|&code| chain.shortest_sequence_length(code) * get_numeric_part_of_code(code))
         .sum()
 }
 
+fn print_shortest_sequences(keypad_codes: &[&[u8]], chain: &KeypadChain<u8>) {
+    for &code in keypad_codes {
+        let sequence: String = chain.shortest_sequence(code).map(char::from).collect();
+        println!("{}: {sequence}", String::from_utf8_lossy(code));
+    }
+}
+
+// what the door keypad types when a human makes the given presses
+fn print_simulation(presses: &str, chain: &KeypadChain<u8>) {
+    match chain.simulate_str(presses) {
+        Ok(keys) => println!("{presses}: {}", String::from_utf8_lossy(&keys)),
+        Err(error) => println!("{presses}: {error}"),
+    }
+}
+
+fn print_shortcut_failures(chain: &KeypadChain<u8>) {
+    let failures = chain.one_turn_shortcut_failures();
+    if failures.is_empty() {
+        println!("Every key pair can be reached with at most one turn");
+    }
+    for (depth, prev_key, next_key) in failures {
+        println!(
+            "Robot keypad {depth}: {} -> {} needs more than one turn",
+            char::from(prev_key),
+            char::from(next_key)
+        );
+    }
+}
+
+// the numeric keypad driven through num_intermediate_robots instruction keypads
+fn robot_chain(
+    numeric_keypad: &Keypad<u8>,
+    instruction_keypad: &Keypad<Instruction>,
+    num_intermediate_robots: usize,
+) -> KeypadChain<u8> {
+    KeypadChain::new(
+        numeric_keypad.clone(),
+        b'A',
+        vec![instruction_keypad.clone(); num_intermediate_robots],
+    )
+}
+
 const NUM_INTERMEDIATE_ROBOTS_PART_1: usize = 2;
 const NUM_INTERMEDIATE_ROBOTS_PART_2: usize = 25;
 
-fn main() {
+const NUMERIC_KEYPAD_STR: &str = include_str!("../numeric_keypad.txt");
+const INSTRUCTION_KEYPAD_STR: &str = include_str!("../instruction_keypad.txt");
+
+fn main() -> Result<(), keypad::Error> {
     const INPUT_STR: &str = include_str!("../input.txt");
 
     let codes = parse_keycodes(INPUT_STR);
+    let numeric_keypad = NUMERIC_KEYPAD_STR.parse()?;
+    let instruction_keypad = INSTRUCTION_KEYPAD_STR.parse()?;
+    let part1_chain = robot_chain(
+        &numeric_keypad,
+        &instruction_keypad,
+        NUM_INTERMEDIATE_ROBOTS_PART_1,
+    );
+    let part2_chain = robot_chain(
+        &numeric_keypad,
+        &instruction_keypad,
+        NUM_INTERMEDIATE_ROBOTS_PART_2,
+    );
+
+    println!("{}", run(&codes, &part1_chain));
+    println!("{}", run(&codes, &part2_chain));
+
+    // --sequences prints a shortest sequence of presses for each code, and
+    // --simulate PRESSES prints what those presses type on the door, both
+    // with part 1's robots. --shortcut-failures lists the key pairs where
+    // part 2's robots need more than one turn.
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.as_slice() {
+        [flag] if flag == "--sequences" => print_shortest_sequences(&codes, &part1_chain),
+        [flag, presses] if flag == "--simulate" => print_simulation(presses, &part1_chain),
+        [flag] if flag == "--shortcut-failures" => print_shortcut_failures(&part2_chain),
+        _ => (),
+    }
 
-    println!("{}", run(&codes, NUM_INTERMEDIATE_ROBOTS_PART_1));
-    println!("{}", run(&codes, NUM_INTERMEDIATE_ROBOTS_PART_2));
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
+
     use super::*;
+    use direction::Direction;
+    use simulator::SimulationError;
 
     const TEST_INPUT_STR: &str = include_str!("../example.txt");
 
     #[test]
     fn test_part1() {
         let codes = parse_keycodes(TEST_INPUT_STR);
-        assert_eq!(126384, run(&codes, NUM_INTERMEDIATE_ROBOTS_PART_1));
+        let numeric_keypad = NUMERIC_KEYPAD_STR.parse().unwrap();
+        let instruction_keypad = INSTRUCTION_KEYPAD_STR.parse().unwrap();
+        let chain = robot_chain(
+            &numeric_keypad,
+            &instruction_keypad,
+            NUM_INTERMEDIATE_ROBOTS_PART_1,
+        );
+        assert_eq!(126384, run(&codes, &chain));
+    }
+
+    #[test]
+    fn test_shortest_sequence() {
+        let numeric_keypad: Keypad<u8> = NUMERIC_KEYPAD_STR.parse().unwrap();
+        let instruction_keypad: Keypad<Instruction> = INSTRUCTION_KEYPAD_STR.parse().unwrap();
+
+        let chain = KeypadChain::new(numeric_keypad.clone(), b'A', vec![]);
+        let sequence: String = chain.shortest_sequence(b"029A").map(char::from).collect();
+        assert!(["<A^A>^^AvvvA", "<A^A^>^AvvvA", "<A^A^^>AvvvA"].contains(&sequence.as_str()));
+
+        let chain = KeypadChain::new(
+            numeric_keypad.clone(),
+            b'A',
+            vec![instruction_keypad.clone(); NUM_INTERMEDIATE_ROBOTS_PART_1],
+        );
+        for code in parse_keycodes(TEST_INPUT_STR) {
+            let length = chain.shortest_sequence_length(code);
+            let sequence = chain.shortest_sequence(code).collect_vec();
+            assert_eq!(length, sequence.len());
+            assert_eq!(Ok(code.to_vec()), chain.simulate(sequence));
+        }
+
+        // far too long to collect, but the first presses come out right away
+        let chain = KeypadChain::new(
+            numeric_keypad,
+            b'A',
+            vec![instruction_keypad; NUM_INTERMEDIATE_ROBOTS_PART_2],
+        );
+        assert_eq!(1000, chain.shortest_sequence(b"029A").take(1000).count());
+    }
+
+    #[test]
+    fn test_simulate() {
+        let numeric_keypad: Keypad<u8> = NUMERIC_KEYPAD_STR.parse().unwrap();
+        let instruction_keypad: Keypad<Instruction> = INSTRUCTION_KEYPAD_STR.parse().unwrap();
+        let chain = KeypadChain::new(
+            numeric_keypad,
+            b'A',
+            vec![instruction_keypad; NUM_INTERMEDIATE_ROBOTS_PART_1],
+        );
+
+        let presses = "<vA<AA>>^AvAA<^A>A<v<A>>^AvA^A<vA>^A<v<A>^A>AAvA^A<v<A>A>^AAAvA<^A>A";
+        assert_eq!(Ok(b"029A".to_vec()), chain.simulate_str(presses));
+
+        // the first robot's arm ends up over the gap next to ^
+        assert_eq!(
+            Err(SimulationError::ArmOverGap {
+                step: 1,
+                keypad: Some(1),
+                position: (0, 0)
+            }),
+            chain.simulate_str("<<")
+        );
+        assert_eq!(
+            Err(SimulationError::ArmOffKeypad {
+                step: 0,
+                keypad: Some(1)
+            }),
+            chain.simulate_str(">")
+        );
+        assert_eq!(
+            Err(SimulationError::UnrecognizedPress { step: 2, c: 'x' }),
+            chain.simulate_str("AAx")
+        );
+    }
+
+    #[test]
+    fn test_phone_keypad_layout() {
+        let phone_keypad: Keypad<u8> = "123\n456\n789\n 0".parse().unwrap();
+        let instruction_keypad = INSTRUCTION_KEYPAD_STR.parse().unwrap();
+
+        // 1 -> 0 has to go >vvvA to avoid the gap in the bottom left
+        let chain = KeypadChain::new(phone_keypad.clone(), b'1', vec![]);
+        assert_eq!(5, chain.shortest_sequence_length(b"0"));
+
+        // vA <A A A ^>A
+        let chain = KeypadChain::new(phone_keypad, b'1', vec![instruction_keypad]);
+        assert_eq!(9, chain.shortest_sequence_length(b"0"));
+    }
+
+    #[test]
+    fn test_irregular_keypad_layouts() {
+        let numeric_keypad: Keypad<u8> = NUMERIC_KEYPAD_STR.parse().unwrap();
+        let instruction_keypad: Keypad<Instruction> = INSTRUCTION_KEYPAD_STR.parse().unwrap();
+
+        let stock_chain = KeypadChain::new(
+            numeric_keypad.clone(),
+            b'A',
+            vec![instruction_keypad.clone(); NUM_INTERMEDIATE_ROBOTS_PART_1],
+        );
+        assert!(stock_chain.one_turn_shortcut_failures().is_empty());
+
+        // ^ -> < has to zig-zag down the staircase through A, v and >
+        let staircase_keypad: Keypad<Instruction> = "^A\n v>\n  <".parse().unwrap();
+        let chain = KeypadChain::new(
+            numeric_keypad,
+            b'A',
+            vec![instruction_keypad, staircase_keypad],
+        );
+        assert!(chain.one_turn_shortcut_failures().contains(&(
+            1,
+            Direction::North.into(),
+            Direction::West.into()
+        )));
+        let sequence = chain.shortest_sequence(b"029A").collect_vec();
+        assert_eq!(chain.shortest_sequence_length(b"029A"), sequence.len());
+        assert_eq!(Ok(b"029A".to_vec()), chain.simulate(sequence));
+
+        // 1 -> 4 steps right, down, then right again
+        let door_keypad: Keypad<u8> = "12\n 34".parse().unwrap();
+        let chain = KeypadChain::new(door_keypad, b'1', vec![]);
+        assert_eq!(4, chain.shortest_sequence_length(b"4"));
+        assert_eq!(Ok(b"4".to_vec()), chain.simulate_str(">v>A"));
     }
 }
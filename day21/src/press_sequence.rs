@@ -0,0 +1,74 @@
+use crate::{instruction::Instruction, keypad::BestPathCache};
+
+// expands the door robot's sequence depth first through the best paths
+// stored in the cache, so only one path per keypad is held at a time
+pub struct PressSequence<'a> {
+    cache: &'a BestPathCache,
+    num_robot_keypads: usize,
+    door_sequence: Vec<Instruction>,
+    door_index: usize,
+    door_prev_key: Instruction,
+    // (depth, prev_key, rest of the path to type on robot_keypads[depth])
+    stack: Vec<(usize, Instruction, &'a [Instruction])>,
+}
+
+impl<'a> PressSequence<'a> {
+    pub fn new(
+        cache: &'a BestPathCache,
+        num_robot_keypads: usize,
+        door_sequence: Vec<Instruction>,
+    ) -> Self {
+        PressSequence {
+            cache,
+            num_robot_keypads,
+            door_sequence,
+            door_index: 0,
+            door_prev_key: Instruction::Activate,
+            stack: Vec::with_capacity(num_robot_keypads),
+        }
+    }
+
+    fn best_path(
+        &self,
+        depth: usize,
+        prev_key: Instruction,
+        next_key: Instruction,
+    ) -> &'a [Instruction] {
+        &self.cache[&(depth, prev_key, next_key)].1
+    }
+}
+
+impl Iterator for PressSequence<'_> {
+    type Item = Instruction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (depth, prev_key, next_key) = match self.stack.last_mut() {
+                Some((depth, prev_key, rest)) => {
+                    let Some((&next_key, new_rest)) = rest.split_first() else {
+                        self.stack.pop();
+                        continue;
+                    };
+                    *rest = new_rest;
+                    let old_prev_key = *prev_key;
+                    *prev_key = next_key;
+                    (*depth, old_prev_key, next_key)
+                }
+                None => {
+                    let &next_key = self.door_sequence.get(self.door_index)?;
+                    self.door_index += 1;
+                    let old_prev_key = self.door_prev_key;
+                    self.door_prev_key = next_key;
+                    (0, old_prev_key, next_key)
+                }
+            };
+
+            if depth == self.num_robot_keypads {
+                return Some(next_key);
+            }
+
+            let path = self.best_path(depth, prev_key, next_key);
+            self.stack.push((depth + 1, Instruction::Activate, path));
+        }
+    }
+}
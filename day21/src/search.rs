@@ -0,0 +1,87 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+};
+
+#[derive(PartialEq, Eq)]
+struct PriorityQueueItem<S> {
+    cost: usize,
+    state: S,
+}
+
+impl<S: Eq> PartialOrd for PriorityQueueItem<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: Eq> Ord for PriorityQueueItem<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cost.cmp(&other.cost).reverse()
+    }
+}
+
+// returns the cost of the best path along with every state on it,
+// starting with start_state and ending with the goal
+pub fn best_path_djikstra<S, F, I, G>(
+    start_state: S,
+    successors: F,
+    is_goal: G,
+) -> Option<(usize, Vec<S>)>
+where
+    S: Eq + Hash + Clone,
+    F: Fn(&S) -> I,
+    I: IntoIterator<Item = (usize, S)>,
+    G: Fn(&S) -> bool,
+{
+    let mut frontier = BinaryHeap::from([PriorityQueueItem {
+        cost: 0,
+        state: start_state.clone(),
+    }]);
+    let mut seen: HashSet<S> = HashSet::new();
+    let mut costs: HashMap<S, usize> = HashMap::from([(start_state, 0)]);
+    let mut predecessors: HashMap<S, S> = HashMap::new();
+
+    while let Some(item) = frontier.pop() {
+        if seen.contains(&item.state) {
+            continue;
+        }
+
+        if is_goal(&item.state) {
+            let mut path = vec![item.state];
+            while let Some(prev_state) = predecessors.get(path.last().unwrap()) {
+                path.push(prev_state.clone());
+            }
+            path.reverse();
+            return Some((item.cost, path));
+        }
+
+        seen.insert(item.state.clone());
+
+        for (edge_cost, successor) in successors(&item.state) {
+            if seen.contains(&successor) {
+                continue;
+            }
+
+            let new_cost = item.cost + edge_cost;
+            if let Some(entry) = costs.get_mut(&successor) {
+                if *entry > new_cost {
+                    *entry = new_cost;
+                } else {
+                    continue;
+                }
+            } else {
+                costs.insert(successor.clone(), new_cost);
+            }
+
+            predecessors.insert(successor.clone(), item.state.clone());
+            frontier.push(PriorityQueueItem {
+                cost: new_cost,
+                state: successor,
+            });
+        }
+    }
+
+    None
+}
@@ -0,0 +1,148 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{direction::move_once_checked, instruction::Instruction, keypad::Keypad};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SimulationError {
+    // keypad is None for the door keypad, otherwise the index into robot_keypads
+    ArmOverGap {
+        step: usize,
+        keypad: Option<usize>,
+        position: (usize, usize),
+    },
+    ArmOffKeypad {
+        step: usize,
+        keypad: Option<usize>,
+    },
+    UnrecognizedPress {
+        step: usize,
+        c: char,
+    },
+}
+
+impl Display for SimulationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use SimulationError::*;
+        let keypad_name = |keypad: &Option<usize>| match keypad {
+            Some(i) => format!("robot keypad {i}"),
+            None => "the door keypad".to_string(),
+        };
+        match self {
+            ArmOverGap {
+                step,
+                keypad,
+                position,
+            } => write!(
+                f,
+                "Press {step} moved the arm over the gap at {position:?} on {}",
+                keypad_name(keypad)
+            ),
+            ArmOffKeypad { step, keypad } => write!(
+                f,
+                "Press {step} moved the arm off the edge of {}",
+                keypad_name(keypad)
+            ),
+            UnrecognizedPress { step, c } => {
+                write!(f, "Press {step} is the unrecognized character '{c}'")
+            }
+        }
+    }
+}
+
+pub struct Simulator<'a, T> {
+    door_keypad: &'a Keypad<T>,
+    robot_keypads: &'a [Keypad<Instruction>],
+    door_position: (usize, usize),
+    robot_positions: Vec<(usize, usize)>,
+}
+
+impl<'a, T: Clone + Eq> Simulator<'a, T> {
+    pub fn new(
+        door_keypad: &'a Keypad<T>,
+        door_start_key: &T,
+        robot_keypads: &'a [Keypad<Instruction>],
+    ) -> Self {
+        Simulator {
+            door_keypad,
+            robot_keypads,
+            door_position: door_keypad.position_of(door_start_key),
+            robot_positions: robot_keypads
+                .iter()
+                .map(|keypad| keypad.position_of(&Instruction::Activate))
+                .collect(),
+        }
+    }
+
+    pub fn run(
+        mut self,
+        presses: impl IntoIterator<Item = Instruction>,
+    ) -> Result<Vec<T>, SimulationError> {
+        let mut output = Vec::new();
+
+        for (step, press) in presses.into_iter().enumerate() {
+            if let Some(key) = self.press(step, press)? {
+                output.push(key);
+            }
+        }
+
+        Ok(output)
+    }
+
+    // the human's press goes to the last robot keypad, each Activate
+    // passes the key under that arm down to the next keypad,
+    // returns the key typed on the door keypad if any
+    fn press(
+        &mut self,
+        step: usize,
+        mut instruction: Instruction,
+    ) -> Result<Option<T>, SimulationError> {
+        for keypad_index in (0..self.robot_keypads.len()).rev() {
+            let keypad = &self.robot_keypads[keypad_index];
+            let position = &mut self.robot_positions[keypad_index];
+
+            match Self::apply(keypad, position, instruction, step, Some(keypad_index))? {
+                Some(&key) => instruction = key,
+                None => return Ok(None),
+            }
+        }
+
+        Self::apply(
+            self.door_keypad,
+            &mut self.door_position,
+            instruction,
+            step,
+            None,
+        )
+        .map(|key| key.cloned())
+    }
+
+    // move the arm or press the key under it
+    fn apply<'k, K: Eq>(
+        keypad: &'k Keypad<K>,
+        position: &mut (usize, usize),
+        instruction: Instruction,
+        step: usize,
+        keypad_index: Option<usize>,
+    ) -> Result<Option<&'k K>, SimulationError> {
+        match instruction {
+            Instruction::Direction(direction) => {
+                let off_keypad = SimulationError::ArmOffKeypad {
+                    step,
+                    keypad: keypad_index,
+                };
+                *position = move_once_checked(*position, direction)
+                    .filter(|&new_position| keypad.contains(new_position))
+                    .ok_or(off_keypad)?;
+                if keypad.get(*position).is_none() {
+                    return Err(SimulationError::ArmOverGap {
+                        step,
+                        keypad: keypad_index,
+                        position: *position,
+                    });
+                }
+                Ok(None)
+            }
+            Instruction::Activate => Ok(keypad.get(*position)),
+        }
+    }
+}